@@ -1,15 +1,141 @@
 use std::fmt::Display;
 
 use anyhow::Result;
+use glob::Pattern;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+  error::SquadError,
+  git::RepoContext,
+  trailer::{Trailer, TrailerConfig},
+  validate::{
+    normalize_email, validate_alias, validate_github_login, validate_name,
+  },
+};
+
+const GITHUB_NOREPLY_DOMAIN: &str = "users.noreply.github.com";
+
 #[derive(
   Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Eq, Ord,
 )]
 pub struct Buddy {
   pub alias: String,
   pub name: String,
+  /// The default email used unless one of the alternative emails matches
   pub email: String,
+  /// Alternative emails selected per repository
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub emails: Vec<AlternativeEmail>,
+  /// GitHub account used to derive a noreply address
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub github: Option<GitHubAccount>,
+}
+
+#[derive(
+  Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Eq, Ord,
+)]
+pub struct GitHubAccount {
+  pub login: String,
+  /// The numeric user id. Required for the `ID+login` noreply format GitHub
+  /// uses for all accounts created after July 2017.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub id: Option<u64>,
+}
+
+impl GitHubAccount {
+  pub fn noreply_email(&self) -> String {
+    match self.id {
+      Some(id) => format!("{id}+{}@{GITHUB_NOREPLY_DOMAIN}", self.login),
+      None => format!("{}@{GITHUB_NOREPLY_DOMAIN}", self.login),
+    }
+  }
+
+  /// Checks if `email` is one of the noreply addresses of this account, with
+  /// or without the numeric id.
+  fn has_noreply_email(&self, email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+      return false;
+    };
+
+    if !domain.eq_ignore_ascii_case(GITHUB_NOREPLY_DOMAIN) {
+      return false;
+    }
+
+    let login = match local.split_once('+') {
+      Some((id, login)) => {
+        if self.id.is_some_and(|own| id != own.to_string()) {
+          return false;
+        }
+        login
+      }
+      None => local,
+    };
+
+    login.eq_ignore_ascii_case(&self.login)
+  }
+}
+
+/// An additional email for a buddy together with the rules that select it
+/// for a repository. An email without any rules is never selected
+/// automatically but still recognised as belonging to the buddy.
+#[derive(
+  Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd, Eq, Ord,
+)]
+pub struct AlternativeEmail {
+  pub email: String,
+  /// Glob pattern matched against the urls of the repository's remotes
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub remote: Option<String>,
+  /// Glob pattern matched against the repository's root directory
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub path: Option<String>,
+}
+
+impl AlternativeEmail {
+  fn matches(&self, repo: &RepoContext) -> bool {
+    if self.remote.is_none() && self.path.is_none() {
+      return false;
+    }
+
+    let remote_matches = self.remote.as_deref().is_none_or(|remote| {
+      Pattern::new(remote).is_ok_and(|pattern| {
+        repo.remotes.iter().any(|url| pattern.matches(url))
+      })
+    });
+
+    let path_matches = self.path.as_deref().is_none_or(|path| {
+      Pattern::new(&expand_home(path)).is_ok_and(|pattern| {
+        repo
+          .path
+          .as_ref()
+          .is_some_and(|repo_path| pattern.matches_path(repo_path))
+      })
+    });
+
+    remote_matches && path_matches
+  }
+
+  fn validated(self) -> Result<AlternativeEmail> {
+    for pattern in [&self.remote, &self.path].into_iter().flatten() {
+      Pattern::new(pattern).map_err(|e| {
+        anyhow::anyhow!("Invalid pattern '{pattern}' for '{}': {e}", self.email)
+      })?;
+    }
+
+    Ok(AlternativeEmail {
+      email: normalize_email(&self.email)?,
+      ..self
+    })
+  }
+}
+
+/// Formats a co-author trailer the way git-squad writes it with `config`.
+pub fn co_author_trailer(
+  name: &str,
+  email: &str,
+  config: &TrailerConfig,
+) -> String {
+  config.render(&Trailer::co_author(name, email))
 }
 
 impl Buddy {
@@ -17,8 +143,80 @@ impl Buddy {
     format!("{} <{}>", self.name, self.email)
   }
 
-  pub fn format_co_author(&self) -> String {
-    format!("Co-authored-by: {}", self.format_buddy())
+  /// Formats the co-author trailer using the email selected for `repo`.
+  pub fn format_co_author(
+    &self,
+    repo: &RepoContext,
+    config: &TrailerConfig,
+  ) -> String {
+    co_author_trailer(&self.name, &self.email_for(repo), config)
+  }
+
+  /// Returns the GitHub noreply address if `repo` asks for it, otherwise the
+  /// first alternative email whose rules match `repo` or the default email if
+  /// none does.
+  pub fn email_for(&self, repo: &RepoContext) -> String {
+    if repo.noreply
+      && let Some(github) = &self.github
+    {
+      return github.noreply_email();
+    }
+
+    self
+      .emails
+      .iter()
+      .find(|alternative| alternative.matches(repo))
+      .map_or(&self.email, |alternative| &alternative.email)
+      .clone()
+  }
+
+  /// All emails of the buddy starting with the default one.
+  pub fn all_emails(&self) -> impl Iterator<Item = &str> {
+    std::iter::once(self.email.as_str()).chain(
+      self
+        .emails
+        .iter()
+        .map(|alternative| alternative.email.as_str()),
+    )
+  }
+
+  pub fn has_email(&self, email: &str) -> bool {
+    self.all_emails().any(|e| e.eq_ignore_ascii_case(email))
+      || self
+        .github
+        .as_ref()
+        .is_some_and(|github| github.has_noreply_email(email))
+  }
+
+  /// Validates all fields of the buddy and returns it with normalised name and
+  /// emails.
+  pub fn validated(self) -> Result<Buddy> {
+    Ok(Buddy {
+      alias: validate_alias(&self.alias)?,
+      name: validate_name(&self.name)?,
+      email: normalize_email(&self.email)?,
+      emails: self
+        .emails
+        .into_iter()
+        .map(AlternativeEmail::validated)
+        .collect::<Result<_>>()?,
+      github: self
+        .github
+        .map(|github| {
+          Ok::<_, anyhow::Error>(GitHubAccount {
+            login: validate_github_login(&github.login)?,
+            ..github
+          })
+        })
+        .transpose()?,
+    })
+  }
+}
+
+fn expand_home(path: &str) -> String {
+  match (path.strip_prefix("~/"), dirs::home_dir()) {
+    (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+    _ => path.to_string(),
   }
 }
 
@@ -47,18 +245,60 @@ impl Buddies {
   }
 
   pub fn get_buddy_by_email(&self, email: &str) -> Option<&Buddy> {
-    self.buddies.iter().find(|buddy| buddy.email == email)
+    self.buddies.iter().find(|buddy| buddy.has_email(email))
   }
 
   pub fn add(&mut self, buddy: Buddy) -> Result<()> {
+    let buddy = buddy.validated()?;
+
     if self.has(&buddy.alias) {
-      anyhow::bail!("Buddy with alias '{}' already exists", buddy.alias);
+      return Err(SquadError::DuplicateAlias { alias: buddy.alias }.into());
+    }
+
+    for email in buddy.all_emails() {
+      if let Some(existing) = self.get_buddy_by_email(email) {
+        anyhow::bail!(
+          "Buddy '{}' already uses email '{email}'",
+          existing.alias
+        );
+      }
     }
 
     self.buddies.push(buddy);
     Ok(())
   }
 
+  /// Checks all buddies for invalid data and duplicate aliases or emails and
+  /// returns a description for every problem found.
+  pub fn problems(&self) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (index, buddy) in self.buddies.iter().enumerate() {
+      if let Err(e) = buddy.clone().validated() {
+        problems.push(format!("Buddy '{}': {e}", buddy.alias));
+      }
+
+      let earlier = &self.buddies[..index];
+      if earlier.iter().any(|b| b.alias == buddy.alias) {
+        problems.push(format!("Duplicate alias '{}'", buddy.alias));
+      }
+
+      for email in buddy.all_emails() {
+        if let Some(other) = earlier
+          .iter()
+          .find(|b| b.all_emails().any(|e| e.eq_ignore_ascii_case(email)))
+        {
+          problems.push(format!(
+            "Buddies '{}' and '{}' share email '{email}'",
+            other.alias, buddy.alias
+          ));
+        }
+      }
+    }
+
+    problems
+  }
+
   pub fn forget(&mut self, alias: &str) -> Result<()> {
     if let Some(index) =
       self.buddies.iter().position(|buddy| buddy.alias == alias)
@@ -67,7 +307,13 @@ impl Buddies {
       return Ok(());
     }
 
-    anyhow::bail!("Buddy with alias '{}' doesn't exist", alias);
+    Err(
+      SquadError::NotFound {
+        query: alias.to_string(),
+        suggestions: vec![],
+      }
+      .into(),
+    )
   }
 }
 
@@ -82,6 +328,8 @@ mod tests {
       alias: "peter".to_string(),
       name: "Peter Pan".to_string(),
       email: "peter.pan@example.com".to_string(),
+      emails: vec![],
+      github: None,
     });
 
     let result = buddies.get_buddy_by_email("peter.pan@example.com");
@@ -102,12 +350,195 @@ mod tests {
       alias: "peter".to_string(),
       name: "Peter Pan".to_string(),
       email: "peter.pan@example.com".to_string(),
+      emails: vec![],
+      github: None,
     };
 
-    let co_author = buddy.format_co_author();
+    let co_author = buddy
+      .format_co_author(&RepoContext::default(), &TrailerConfig::default());
     assert_eq!(
       co_author,
       "Co-authored-by: Peter Pan <peter.pan@example.com>".to_string()
     );
   }
+
+  #[test]
+  fn test_email_for() {
+    let buddy = Buddy {
+      alias: "peter".to_string(),
+      name: "Peter Pan".to_string(),
+      email: "peter@neverland.org".to_string(),
+      emails: vec![
+        AlternativeEmail {
+          email: "peter.pan@corp.example.com".to_string(),
+          remote: Some("git@corp.example.com:*".to_string()),
+          path: None,
+        },
+        AlternativeEmail {
+          email: "peter@lostboys.org".to_string(),
+          remote: None,
+          path: Some("/src/lostboys/**".to_string()),
+        },
+        AlternativeEmail {
+          email: "pan@example.com".to_string(),
+          remote: None,
+          path: None,
+        },
+      ],
+      github: None,
+    };
+
+    let corp = RepoContext {
+      path: Some("/src/app".into()),
+      remotes: vec!["git@corp.example.com:team/app.git".to_string()],
+      noreply: false,
+    };
+    assert_eq!(buddy.email_for(&corp), "peter.pan@corp.example.com");
+
+    let lostboys = RepoContext {
+      path: Some("/src/lostboys/camp".into()),
+      remotes: vec!["https://github.com/lostboys/camp".to_string()],
+      noreply: false,
+    };
+    assert_eq!(buddy.email_for(&lostboys), "peter@lostboys.org");
+
+    assert_eq!(
+      buddy.email_for(&RepoContext::default()),
+      "peter@neverland.org"
+    );
+
+    let buddies = Buddies::new(vec![buddy]);
+    for email in ["peter.pan@corp.example.com", "pan@example.com"] {
+      assert_eq!(buddies.get_buddy_by_email(email).unwrap().alias, "peter");
+    }
+  }
+
+  #[test]
+  fn test_github_noreply() {
+    let buddy = Buddy {
+      alias: "peter".to_string(),
+      name: "Peter Pan".to_string(),
+      email: "peter@neverland.org".to_string(),
+      emails: vec![],
+      github: Some(GitHubAccount {
+        login: "peterpan".to_string(),
+        id: Some(1904),
+      }),
+    };
+
+    let repo = RepoContext {
+      noreply: true,
+      ..RepoContext::default()
+    };
+    assert_eq!(
+      buddy.format_co_author(&repo, &TrailerConfig::default()),
+      "Co-authored-by: Peter Pan <1904+peterpan@users.noreply.github.com>"
+    );
+    assert_eq!(
+      buddy
+        .format_co_author(&RepoContext::default(), &TrailerConfig::default()),
+      "Co-authored-by: Peter Pan <peter@neverland.org>"
+    );
+
+    assert!(buddy.has_email("1904+peterpan@users.noreply.github.com"));
+    assert!(buddy.has_email("PeterPan@users.noreply.github.com"));
+    assert!(!buddy.has_email("1905+peterpan@users.noreply.github.com"));
+    assert!(!buddy.has_email("wendy@users.noreply.github.com"));
+  }
+
+  #[test]
+  fn test_add_rejects_invalid_and_duplicate_buddies() {
+    let mut buddies = Buddies::default();
+    buddies
+      .add(Buddy {
+        alias: "peter".to_string(),
+        name: "Peter Pan".to_string(),
+        email: "peter.pan@example.com".to_string(),
+        emails: vec![],
+        github: None,
+      })
+      .unwrap();
+
+    let injected = buddies.add(Buddy {
+      alias: "hook".to_string(),
+      name: "Captain Hook".to_string(),
+      email: "hook@example.com>\nCo-authored-by: Smee <smee@example.com"
+        .to_string(),
+      emails: vec![],
+      github: None,
+    });
+    assert!(injected.is_err());
+
+    let duplicate_email = buddies.add(Buddy {
+      alias: "pan".to_string(),
+      name: "Peter Pan".to_string(),
+      email: "Peter.Pan@example.com".to_string(),
+      emails: vec![],
+      github: None,
+    });
+    assert!(duplicate_email.is_err());
+    assert_eq!(buddies.buddies.len(), 1);
+  }
+
+  #[test]
+  fn test_problems() {
+    let buddies = Buddies::new(vec![
+      Buddy {
+        alias: "peter".to_string(),
+        name: "Peter <Pan>".to_string(),
+        email: "peter.pan@example.com".to_string(),
+        emails: vec![],
+        github: None,
+      },
+      Buddy {
+        alias: "peter".to_string(),
+        name: "Peter Pan".to_string(),
+        email: "peter.pan@example.com".to_string(),
+        emails: vec![],
+        github: None,
+      },
+    ]);
+
+    assert_eq!(
+      buddies.problems(),
+      vec![
+        "Buddy 'peter': Name 'Peter <Pan>' must not contain '<' or '>'",
+        "Duplicate alias 'peter'",
+        "Buddies 'peter' and 'peter' share email 'peter.pan@example.com'",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_problems_alternative_emails() {
+    let buddies = Buddies::new(vec![
+      Buddy {
+        alias: "peter".to_string(),
+        name: "Peter Pan".to_string(),
+        email: "peter@neverland.org".to_string(),
+        emails: vec![AlternativeEmail {
+          email: "pan@example.com".to_string(),
+          remote: None,
+          path: None,
+        }],
+        github: None,
+      },
+      Buddy {
+        alias: "wendy".to_string(),
+        name: "Wendy Darling".to_string(),
+        email: "wendy@example.com".to_string(),
+        emails: vec![AlternativeEmail {
+          email: "Pan@example.com".to_string(),
+          remote: None,
+          path: None,
+        }],
+        github: None,
+      },
+    ]);
+
+    assert_eq!(
+      buddies.problems(),
+      vec!["Buddies 'peter' and 'wendy' share email 'Pan@example.com'"]
+    );
+  }
 }
@@ -1,6 +1,14 @@
-use std::{io, path::PathBuf};
+use std::{
+  env,
+  ffi::{OsStr, OsString},
+  io,
+  path::PathBuf,
+};
 
-use clap::{CommandFactory, Parser, Subcommand, builder::StyledStr};
+use clap::{
+  CommandFactory, Parser, Subcommand,
+  builder::{PossibleValuesParser, StyledStr},
+};
 use clap_complete::{
   CompleteEnv, Shell,
   engine::{ArgValueCompleter, CompletionCandidate},
@@ -8,7 +16,15 @@ use clap_complete::{
   generate,
 };
 
-use crate::config::{ConfigService, FileConfig};
+use crate::{
+  buddy::Buddy,
+  config::{ConfigService, FileConfig},
+  error::ErrorFormat,
+  git, hook,
+  output::OutputFormat,
+  resolve,
+  verify::ReportFormat,
+};
 
 #[derive(Debug, Parser)]
 #[command(name = "git-squad")]
@@ -20,6 +36,34 @@ pub struct Cli {
   /// Use a custom buddy file instead of ~/.config/git-squad/buddies.toml
   #[arg(long = "buddies-file", global = true)]
   pub buddies_file: Option<PathBuf>,
+
+  /// Run as if git-squad was started in <PATH> instead of the current
+  /// directory
+  #[arg(short = 'C', long = "repo", value_name = "PATH", global = true)]
+  pub repo: Option<PathBuf>,
+
+  /// Use GitHub noreply addresses for buddies with a GitHub account. Set
+  /// `git config squad.noreply true` to make this the default for a
+  /// repository
+  #[arg(long = "noreply", global = true)]
+  pub noreply: bool,
+
+  /// Output format for commands that print buddies
+  #[arg(long = "format", global = true, value_enum, default_value_t)]
+  format: OutputFormat,
+
+  /// Stable output for scripts, shorthand for `--format porcelain`
+  #[arg(long = "porcelain", global = true, conflicts_with = "format")]
+  porcelain: bool,
+
+  /// Print a diff of the changes to the commit template and the buddies
+  /// file instead of writing them
+  #[arg(long = "dry-run", global = true)]
+  pub dry_run: bool,
+
+  /// How errors are reported on stderr
+  #[arg(long = "error-format", global = true, value_enum, default_value_t)]
+  pub error_format: ErrorFormat,
 }
 
 impl Cli {
@@ -31,6 +75,14 @@ impl Cli {
   pub fn get_command(&self) -> Command {
     self.command.clone().unwrap_or(Command::Info)
   }
+
+  pub fn output_format(&self) -> OutputFormat {
+    if self.porcelain {
+      OutputFormat::Porcelain
+    } else {
+      self.format
+    }
+  }
 }
 
 pub fn print_completions(shell: Shell) -> anyhow::Result<()> {
@@ -66,29 +118,120 @@ pub enum Command {
   /// Add buddies to the current session
   With {
     /// The aliases of the buddies to add
-    #[arg(add = ArgValueCompleter::new(alias_completer))]
+    #[arg(add = ArgValueCompleter::new(inactive_alias_completer))]
     aliases: Vec<String>,
+
+    /// Fail without changing the session if any buddy is unknown or already
+    /// active. Set `git config squad.strict true` to make this the default
+    #[arg(long = "strict")]
+    strict: bool,
+
+    /// Only add the buddies for the next commit. Installs a post-commit hook
+    /// that removes them again
+    #[arg(long = "once")]
+    once: bool,
+
+    /// Add the buddies in all repositories in a directory or listed in a
+    /// file
+    #[arg(
+      long = "all-repos",
+      value_name = "DIR|FILE",
+      conflicts_with = "repo"
+    )]
+    all_repos: Option<PathBuf>,
+
+    /// Also update all initialized submodules. Set `git config
+    /// squad.recurseSubmodules true` to make this the default
+    #[arg(long = "recurse-submodules")]
+    recurse_submodules: bool,
+
+    /// Bind the resulting session to the current branch. Run `git squad hook
+    /// install` to switch sessions automatically on checkout
+    #[arg(long = "branch")]
+    branch: bool,
   },
 
   /// Remove buddies from the current session
   Without {
     /// The aliases of the buddies to remove
-    #[arg(add = ArgValueCompleter::new(alias_completer))]
+    #[arg(add = ArgValueCompleter::new(active_alias_completer))]
     aliases: Vec<String>,
+
+    /// Fail without changing the session if any buddy is unknown or not
+    /// active. Set `git config squad.strict true` to make this the default
+    #[arg(long = "strict")]
+    strict: bool,
+
+    /// Remove the buddies in all repositories in a directory or listed in a
+    /// file
+    #[arg(
+      long = "all-repos",
+      value_name = "DIR|FILE",
+      conflicts_with = "repo"
+    )]
+    all_repos: Option<PathBuf>,
+
+    /// Also update all initialized submodules. Set `git config
+    /// squad.recurseSubmodules true` to make this the default
+    #[arg(long = "recurse-submodules")]
+    recurse_submodules: bool,
+
+    /// Bind the resulting session to the current branch. Run `git squad hook
+    /// install` to switch sessions automatically on checkout
+    #[arg(long = "branch")]
+    branch: bool,
   },
 
   /// Remove all buddies from the current session
-  Alone,
+  Alone {
+    /// End the sessions in all repositories in a directory or listed in a
+    /// file
+    #[arg(
+      long = "all-repos",
+      value_name = "DIR|FILE",
+      conflicts_with = "repo"
+    )]
+    all_repos: Option<PathBuf>,
+
+    /// Also update all initialized submodules. Set `git config
+    /// squad.recurseSubmodules true` to make this the default
+    #[arg(long = "recurse-submodules")]
+    recurse_submodules: bool,
+
+    /// Bind the resulting session to the current branch. Run `git squad hook
+    /// install` to switch sessions automatically on checkout
+    #[arg(long = "branch")]
+    branch: bool,
+  },
+
+  /// Add co-authors of the current session that are not in the buddies file
+  /// as buddies
+  Adopt {
+    /// The email of the co-author to add, all unknown co-authors if omitted
+    email: Option<String>,
+
+    /// The alias for the new buddy, prompted for if omitted
+    #[arg(long = "alias", requires = "email")]
+    alias: Option<String>,
+  },
 
   /// Create a new buddy
   Create {
     /// The alias for the new buddy
     alias: String,
+
+    /// The GitHub login of the new buddy
+    #[arg(long = "github")]
+    github: Option<String>,
+
+    /// The numeric GitHub user id of the new buddy
+    #[arg(long = "github-id", requires = "github")]
+    github_id: Option<u64>,
   },
 
   /// Delete a buddy from the list of available buddies
   Forget {
-    /// The alias for the buddy to delete
+    /// The buddy to delete, anything but the exact alias has to be confirmed
     #[arg(add = ArgValueCompleter::new(alias_completer))]
     alias: String,
   },
@@ -100,7 +243,66 @@ pub enum Command {
   List,
 
   /// List active buddies in the current session
-  Active,
+  Active {
+    /// List the active buddies of all repositories in a directory or listed
+    /// in a file
+    #[arg(
+      long = "all-repos",
+      value_name = "DIR|FILE",
+      conflicts_with = "repo"
+    )]
+    all_repos: Option<PathBuf>,
+  },
+
+  /// List the commits recorded by the post-commit hook, grouped by session
+  Ledger,
+
+  /// Print a short summary of the current session for shell prompts
+  Prompt {
+    /// The template for the output. Supports the placeholders `{aliases}`,
+    /// `{names}`, `{emails}` and `{count}`. Nothing is printed if no buddies
+    /// are active.
+    #[arg(default_value = "{aliases}")]
+    template: String,
+  },
+
+  /// Check the buddies file for invalid or duplicate entries
+  CheckBuddies,
+
+  /// Diagnose problems with the git config, the commit template, the buddies
+  /// file and the hooks
+  Doctor {
+    /// Repair the problems that can be fixed automatically
+    #[arg(long = "fix")]
+    fix: bool,
+  },
+
+  /// Check the co-authors of a commit message against the buddies and the
+  /// policy in `.git-squad-policy.toml`
+  LintMessage {
+    /// The file containing the commit message
+    file: PathBuf,
+  },
+
+  /// Check the co-authors of all commits in a revision range, e.g. in CI
+  Verify {
+    /// The commits to check, e.g. `origin/main..HEAD`
+    range: String,
+
+    /// The format of the report
+    #[arg(long = "report", value_enum, default_value_t)]
+    report: ReportFormat,
+
+    /// Map co-authors with the mailmap of the repository before checking them
+    #[arg(long = "mailmap")]
+    mailmap: bool,
+  },
+
+  /// Manage the git hooks of git-squad
+  Hook {
+    #[command(subcommand)]
+    command: HookCommand,
+  },
 
   /// Generate completions for your shell
   Completions { shell: Shell },
@@ -114,26 +316,175 @@ pub enum Command {
   },
 }
 
-fn alias_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
-  // TODO: support completions with custom buddies_file locations
-  let conf = FileConfig { buddies_file: None };
+#[derive(Debug, Subcommand, Clone)]
+pub enum HookCommand {
+  /// Install the git hooks of git-squad in the repository
+  Install {
+    /// Replace existing hooks that were not installed by git-squad
+    #[arg(long = "force")]
+    force: bool,
+
+    /// Only install this hook, may be given multiple times
+    #[arg(
+      long = "hook",
+      value_name = "HOOK",
+      value_parser = PossibleValuesParser::new(hook::HOOKS)
+    )]
+    hooks: Vec<String>,
+
+    /// Also install the hooks in all initialized submodules. Set `git config
+    /// squad.recurseSubmodules true` to make this the default
+    #[arg(long = "recurse-submodules")]
+    recurse_submodules: bool,
+  },
+
+  /// Remove the git hooks installed by git-squad
+  Uninstall {
+    /// Also remove the hooks from all initialized submodules
+    #[arg(long = "recurse-submodules")]
+    recurse_submodules: bool,
+  },
+
+  /// Run a hook, called by the installed hook scripts
+  #[command(hide = true)]
+  Run {
+    hook: String,
+
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+  },
+}
+
+/// The command line that is being completed, parsed as far as possible
+struct CompletionContext {
+  conf: FileConfig,
+  repo: git::Repo,
+  /// Values of the positional arguments other than the one being completed
+  present: Vec<String>,
+}
+
+impl CompletionContext {
+  fn from_env(current: &OsStr) -> Self {
+    // The shell passes the command line after `--`
+    let args: Vec<OsString> = env::args_os()
+      .skip_while(|arg| arg != "--")
+      .skip(1)
+      .collect();
+
+    let matches = Cli::command()
+      .ignore_errors(true)
+      .try_get_matches_from(args)
+      .ok();
 
-  if let Ok(buddies) = conf.load_buddies() {
+    let buddies_file = matches.as_ref().and_then(|matches| {
+      matches
+        .try_get_one::<PathBuf>("buddies_file")
+        .ok()
+        .flatten()
+        .cloned()
+    });
+
+    let repo_dir = matches.as_ref().and_then(|matches| {
+      matches
+        .try_get_one::<PathBuf>("repo")
+        .ok()
+        .flatten()
+        .cloned()
+    });
+
+    let mut present: Vec<String> = matches
+      .as_ref()
+      .and_then(|matches| matches.subcommand())
+      .into_iter()
+      .flat_map(|(_, sub_matches)| {
+        ["aliases", "alias"].into_iter().flat_map(|id| {
+          sub_matches
+            .try_get_many::<String>(id)
+            .ok()
+            .flatten()
+            .into_iter()
+            .flatten()
+            .cloned()
+        })
+      })
+      .collect();
+
+    if let Some(index) =
+      present.iter().position(|value| current == value.as_str())
+    {
+      present.remove(index);
+    }
+
+    CompletionContext {
+      conf: FileConfig {
+        buddies_file,
+        // Completions must never write anything
+        dry_run: true,
+      },
+      repo: git::Repo::new(repo_dir),
+      present,
+    }
+  }
+
+  fn candidates(
+    &self,
+    buddies: &[&Buddy],
+    current: &OsStr,
+  ) -> Vec<CompletionCandidate> {
     let current = current.to_str().unwrap_or_default();
-    return buddies
-      .buddies
+
+    buddies
       .iter()
-      .filter(|s| {
-        s.alias.starts_with(current)
-          || s.name.starts_with(current)
-          || s.email.starts_with(current)
-      })
+      .filter(|b| !self.present.contains(&b.alias))
+      .filter(|b| resolve::matches_prefix(b, current))
       .map(|b| {
         let help = Some(StyledStr::from(b.format_buddy()));
         CompletionCandidate::new(b.alias.clone()).help(help)
       })
-      .collect();
+      .collect()
   }
+}
+
+fn alias_completer(current: &OsStr) -> Vec<CompletionCandidate> {
+  let context = CompletionContext::from_env(current);
+  let Ok(buddies) = context.conf.load_buddies() else {
+    return vec![];
+  };
+
+  context.candidates(&buddies.buddies.iter().collect::<Vec<_>>(), current)
+}
+
+fn inactive_alias_completer(current: &OsStr) -> Vec<CompletionCandidate> {
+  let context = CompletionContext::from_env(current);
+  let Ok(buddies) = context.conf.load_buddies() else {
+    return vec![];
+  };
+  let active_buddies = context
+    .repo
+    .get_active_buddies(&buddies)
+    .unwrap_or_default();
+  let self_aliases = context.repo.self_aliases(&buddies).unwrap_or_default();
+
+  let inactive: Vec<_> = buddies
+    .buddies
+    .iter()
+    .filter(|b| !active_buddies.has(&b.alias))
+    .filter(|b| !self_aliases.contains(&b.alias))
+    .collect();
+
+  context.candidates(&inactive, current)
+}
+
+fn active_alias_completer(current: &OsStr) -> Vec<CompletionCandidate> {
+  let context = CompletionContext::from_env(current);
+  let Ok(active_buddies) = context
+    .conf
+    .load_buddies()
+    .and_then(|buddies| context.repo.get_active_buddies(&buddies))
+  else {
+    return vec![];
+  };
 
-  vec![]
+  let active: Vec<_> = active_buddies.buddies.iter().collect();
+  context.candidates(&active, current)
 }
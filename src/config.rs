@@ -7,7 +7,11 @@ use std::{
 use anyhow::{Context, Result};
 use dirs::home_dir;
 
-use crate::buddy::Buddies;
+use crate::{
+  buddy::Buddies,
+  error::{self, SquadError},
+  write,
+};
 
 pub trait ConfigService {
   fn load_buddies(&self) -> Result<Buddies>;
@@ -16,6 +20,8 @@ pub trait ConfigService {
 
 pub struct FileConfig {
   pub buddies_file: Option<PathBuf>,
+  /// Print a diff instead of writing the buddies file
+  pub dry_run: bool,
 }
 
 impl FileConfig {
@@ -49,7 +55,7 @@ impl ConfigService for FileConfig {
       return Ok(Buddies::default());
     }
 
-    let mut file = File::open(path).context("Failed to open config file")?;
+    let mut file = File::open(&path).context("Failed to open config file")?;
 
     let mut contents = String::new();
     file
@@ -60,7 +66,18 @@ impl ConfigService for FileConfig {
       return Ok(Buddies::default());
     }
 
-    toml::from_str(&contents).context("Failed to parse config")
+    toml::from_str(&contents).map_err(|e| {
+      let offset = e.span().map_or(0, |span| span.start);
+      let (line, column) = error::line_column(&contents, offset);
+
+      SquadError::ConfigParse {
+        path,
+        line,
+        column,
+        message: e.message().to_string(),
+      }
+      .into()
+    })
   }
 
   fn save_buddies(&self, config: &Buddies) -> Result<()> {
@@ -69,14 +86,7 @@ impl ConfigService for FileConfig {
     let contents =
       toml::to_string(config).context("Failed to serialize config")?;
 
-    let mut file =
-      File::create(path).context("Failed to create config file")?;
-
-    file
-      .write_all(contents.as_bytes())
-      .context("Failed to write to buddies file")?;
-
-    Ok(())
+    write::write_file(&path, &contents, self.dry_run)
   }
 }
 
@@ -104,7 +114,13 @@ impl DeprecatedFileConfig {
   }
 
   pub fn migrate(&self, to: &FileConfig) -> Result<()> {
-    let buddies = self.load_buddies()?;
+    let mut buddies = Buddies::default();
+    for buddy in self.load_buddies()?.buddies {
+      let alias = buddy.alias.clone();
+      buddies
+        .add(buddy)
+        .with_context(|| format!("Failed to migrate buddy '{alias}'"))?;
+    }
 
     to.save_buddies(&buddies)
   }
@@ -0,0 +1,265 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+  config::ConfigService, error::SquadError, git::Repo, hook, session::Sessions,
+  template::Template, trailer::TrailerConfig, write,
+};
+
+/// The boolean settings of git-squad, which fail commands if they are invalid
+const BOOL_SETTINGS: [&str; 3] =
+  ["squad.noreply", "squad.strict", "squad.recurseSubmodules"];
+
+/// A problem found by [`run`]
+struct Finding {
+  message: String,
+  repair: Option<Repair>,
+}
+
+/// How `doctor --fix` repairs a [`Finding`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Repair {
+  /// Rewrite the commit template with the repaired contents
+  Template(String),
+  /// Install the git-squad version of the hook
+  Hook(&'static str),
+}
+
+impl Finding {
+  fn new(message: String) -> Self {
+    Finding {
+      message,
+      repair: None,
+    }
+  }
+}
+
+/// Checks the git config, the commit template, the buddies file and the hooks
+/// of `repo` for problems, and repairs the ones it can with `fix`. Fails if
+/// problems remain.
+pub fn run(
+  conf: &impl ConfigService,
+  repo: &Repo,
+  buddies_file: Option<&Path>,
+  fix: bool,
+  dry_run: bool,
+) -> Result<()> {
+  let checks = [
+    ("git config", check_config(repo)),
+    ("commit template", check_template(repo)?),
+    ("buddies file", check_buddies(conf)),
+    ("hooks", check_hooks(repo, buddies_file)?),
+  ];
+
+  let mut remaining = 0;
+  let mut fixable = 0;
+  let mut repairs = Vec::new();
+
+  for (name, findings) in &checks {
+    if findings.is_empty() {
+      println!("Checking {name}: ok");
+      continue;
+    }
+
+    println!("Checking {name}: {} problem(s)", findings.len());
+    for finding in findings {
+      let status = match &finding.repair {
+        Some(repair) if fix => {
+          if !repairs.contains(repair) {
+            repairs.push(repair.clone());
+          }
+          " (fixed)"
+        }
+        Some(_) => {
+          remaining += 1;
+          fixable += 1;
+          " (fixable with --fix)"
+        }
+        None => {
+          remaining += 1;
+          ""
+        }
+      };
+      println!("- {}{status}", finding.message);
+    }
+  }
+
+  for repair in &repairs {
+    match repair {
+      Repair::Template(contents) => {
+        let path = repo.get_commit_template_path()?;
+        write::write_file(&path, contents, dry_run)
+          .context("Failed to write to commit template file")?;
+        println!("Repaired commit template '{}'", path.display());
+      }
+      Repair::Hook(name) => {
+        hook::install_hook(repo, buddies_file, name, false, dry_run)?;
+      }
+    }
+  }
+
+  if remaining > 0 {
+    if fixable > 0 {
+      anyhow::bail!(
+        "Found {remaining} problem(s), {fixable} can be fixed with --fix"
+      );
+    }
+    anyhow::bail!("Found {remaining} problem(s)");
+  }
+
+  if repairs.is_empty() {
+    println!("No problems found.");
+  } else {
+    println!("Fixed all problems.");
+  }
+
+  Ok(())
+}
+
+fn check_config(repo: &Repo) -> Vec<Finding> {
+  BOOL_SETTINGS
+    .iter()
+    .filter_map(|key| repo.get_config_bool(key).err())
+    .map(|e| Finding::new(e.to_string()))
+    .collect()
+}
+
+fn check_template(repo: &Repo) -> Result<Vec<Finding>> {
+  let path = match repo.get_commit_template_path() {
+    Ok(path) => path,
+    Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+      return Ok(vec![Finding::new(
+        "No commit template configured, set one with `git config \
+         commit.template <file>`"
+          .to_string(),
+      )]);
+    }
+    Err(e) => return Err(e),
+  };
+
+  if !path.exists() {
+    // A template without directory is in the directory git runs in
+    let dir = match path.parent() {
+      Some(dir) if !dir.as_os_str().is_empty() => dir,
+      _ => return Ok(vec![]),
+    };
+    if dir.exists() {
+      return Ok(vec![]);
+    }
+    return Ok(vec![Finding::new(format!(
+      "Template dir '{}' doesn't exist",
+      dir.display()
+    ))]);
+  }
+
+  let Some(template) = read_template(repo)? else {
+    return Ok(vec![]);
+  };
+  let format = repo.section_format()?;
+  let repair = Repair::Template(template.repair(&format));
+
+  let mut findings: Vec<Finding> = template
+    .problems
+    .iter()
+    .map(|problem| Finding {
+      message: format!("line {}: {}", problem.line, problem.kind),
+      repair: Some(repair.clone()),
+    })
+    .collect();
+
+  // Sections written before core.commentChar or commit.cleanup changed
+  if let Some(current) = template.format()
+    && *current != format
+  {
+    findings.push(Finding {
+      message: format!(
+        "git-squad section uses {current} instead of {format}, check \
+         core.commentChar and commit.cleanup"
+      ),
+      repair: Some(repair),
+    });
+  }
+
+  Ok(findings)
+}
+
+/// The commit template of `repo` parsed without failing on its problems,
+/// `None` if there is none
+fn read_template(repo: &Repo) -> Result<Option<Template>> {
+  let path = match repo.get_commit_template_path() {
+    Ok(path) => path,
+    Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+      return Ok(None);
+    }
+    Err(e) => return Err(e),
+  };
+
+  if !path.exists() {
+    return Ok(None);
+  }
+
+  let contents =
+    fs::read_to_string(&path).context("Failed to read commit template file")?;
+  Ok(Some(Template::parse(
+    &contents,
+    &TrailerConfig::load(repo)?,
+    &repo.placeholder()?,
+  )))
+}
+
+fn check_buddies(conf: &impl ConfigService) -> Vec<Finding> {
+  match conf.load_buddies() {
+    Ok(buddies) => buddies.problems().into_iter().map(Finding::new).collect(),
+    Err(e) => vec![Finding::new(e.to_string())],
+  }
+}
+
+fn check_hooks(
+  repo: &Repo,
+  buddies_file: Option<&Path>,
+) -> Result<Vec<Finding>> {
+  let mut findings = Vec::new();
+
+  for name in hook::HOOKS {
+    if hook::is_outdated(repo, buddies_file, name)? {
+      findings.push(Finding {
+        message: format!(
+          "Hook '{name}' runs another git-squad executable or buddies file"
+        ),
+        repair: Some(Repair::Hook(name)),
+      });
+    }
+  }
+
+  // Hooks that features in use depend on
+  let mut required = Vec::new();
+  if !Sessions::load(repo)?.branches.is_empty() {
+    required.push(("post-checkout", "Sessions are bound to branches"));
+  }
+  if read_template(repo)?
+    .is_some_and(|template| !template.one_shot_aliases().is_empty())
+  {
+    required.push(("post-commit", "One-shot buddies are active"));
+  }
+
+  for (name, reason) in required {
+    if hook::is_installed(repo, name) {
+      continue;
+    }
+
+    findings.push(if hook::is_foreign(repo, name) {
+      Finding::new(format!(
+        "{reason}, but hook '{name}' was not installed by git-squad, replace \
+         it with `git squad hook install --force`"
+      ))
+    } else {
+      Finding {
+        message: format!("{reason}, but hook '{name}' is not installed"),
+        repair: Some(Repair::Hook(name)),
+      }
+    });
+  }
+
+  Ok(findings)
+}
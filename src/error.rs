@@ -0,0 +1,182 @@
+use std::{fmt::Write, path::PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Errors of the git-squad domain. Every variant has a stable exit code and
+/// a stable `code` in JSON error output. All other errors exit with
+/// [`EXIT_FAILURE`].
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum SquadError {
+  #[error("Buddy with alias '{query}' does not exist{}", format_suggestions(.suggestions))]
+  NotFound {
+    query: String,
+    /// Similar buddies formatted for display
+    suggestions: Vec<String>,
+  },
+
+  #[error("Several buddies match '{query}': {}", .aliases.join(", "))]
+  Ambiguous { query: String, aliases: Vec<String> },
+
+  #[error("Buddy '{alias}' is already active")]
+  AlreadyActive { alias: String },
+
+  #[error("Buddy '{alias}' is not active")]
+  NotActive { alias: String },
+
+  #[error(
+    "No template file set. Configure one using `git config commit.template \
+     /path/to/template/file`"
+  )]
+  NoTemplate,
+
+  #[error("Buddy with alias '{alias}' already exists")]
+  DuplicateAlias { alias: String },
+
+  #[error("Failed to parse commit template {}:{line}: {message}", .path.display())]
+  TemplateParse {
+    path: PathBuf,
+    line: usize,
+    message: String,
+  },
+
+  #[error("Failed to parse buddies file {}:{line}:{column}: {message}", .path.display())]
+  ConfigParse {
+    path: PathBuf,
+    line: usize,
+    column: usize,
+    message: String,
+  },
+
+  #[error("Failed to run `git {command}`: {message}")]
+  Git { command: String, message: String },
+}
+
+pub const EXIT_FAILURE: u8 = 1;
+
+impl SquadError {
+  pub fn exit_code(&self) -> u8 {
+    match self {
+      SquadError::NotFound { .. } => 3,
+      SquadError::Ambiguous { .. } => 4,
+      SquadError::AlreadyActive { .. } => 5,
+      SquadError::NotActive { .. } => 6,
+      SquadError::NoTemplate => 7,
+      SquadError::DuplicateAlias { .. } => 8,
+      SquadError::TemplateParse { .. } => 9,
+      SquadError::ConfigParse { .. } => 10,
+      SquadError::Git { .. } => 11,
+    }
+  }
+}
+
+/// How errors are reported on stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+  /// A human readable message including the causes
+  #[default]
+  Human,
+  /// A single line JSON object
+  Json,
+}
+
+/// The JSON representation of an error
+#[derive(Debug, Serialize)]
+struct ErrorRecord<'a> {
+  /// The message including all causes
+  message: String,
+  exit_code: u8,
+  #[serde(flatten)]
+  details: Option<&'a SquadError>,
+}
+
+/// Returns the exit code for `error`, looking through any added context.
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+  error
+    .downcast_ref::<SquadError>()
+    .map_or(EXIT_FAILURE, SquadError::exit_code)
+}
+
+/// Formats `error` for stderr. JSON errors not caused by a [`SquadError`]
+/// have the code `error`.
+pub fn render(error: &anyhow::Error, format: ErrorFormat) -> String {
+  match format {
+    ErrorFormat::Human => format!("Error: {error:?}"),
+    ErrorFormat::Json => {
+      let details = error.downcast_ref::<SquadError>();
+      let record = ErrorRecord {
+        message: format!("{error:#}"),
+        exit_code: exit_code(error),
+        details,
+      };
+
+      let mut json = serde_json::to_value(&record)
+        .unwrap_or_else(|_| serde_json::json!({ "message": record.message }));
+      if details.is_none() {
+        json["code"] = "error".into();
+      }
+      json.to_string()
+    }
+  }
+}
+
+/// Converts the byte offset `offset` in `contents` to a one based line and
+/// column.
+pub fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+  let before = &contents[..offset.min(contents.len())];
+  let line = before.matches('\n').count() + 1;
+  let column = before
+    .rfind('\n')
+    .map_or(before, |newline| &before[newline + 1..])
+    .chars()
+    .count()
+    + 1;
+
+  (line, column)
+}
+
+fn format_suggestions(suggestions: &[String]) -> String {
+  let mut out = String::new();
+
+  if !suggestions.is_empty() {
+    out.push_str("\nDid you mean:");
+    for suggestion in suggestions {
+      let _ = write!(out, "\n  {suggestion}");
+    }
+  }
+
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_line_column() {
+    let contents = "[[buddies]]\nalias = \"pp\"\nname = ";
+    assert_eq!(line_column(contents, 0), (1, 1));
+    assert_eq!(line_column(contents, 12), (2, 1));
+    assert_eq!(line_column(contents, 20), (2, 9));
+    assert_eq!(line_column(contents, 100), (3, 8));
+  }
+
+  #[test]
+  fn test_render_json() {
+    let error = anyhow::Error::from(SquadError::AlreadyActive {
+      alias: "pp".to_string(),
+    });
+    assert_eq!(
+      render(&error, ErrorFormat::Json),
+      r#"{"alias":"pp","code":"already_active","exit_code":5,"message":"Buddy 'pp' is already active"}"#
+    );
+
+    let error = anyhow::anyhow!("Something else");
+    assert_eq!(
+      render(&error, ErrorFormat::Json),
+      r#"{"code":"error","exit_code":1,"message":"Something else"}"#
+    );
+  }
+}
@@ -1,151 +1,745 @@
 use std::{
   fs::File,
-  io::{Read, Write},
-  path::PathBuf,
-  process::Command,
+  io::Read,
+  path::{Path, PathBuf},
+  process::{Command, Output},
 };
 
 use anyhow::{Context, Result};
-use regex::Regex;
 
-use crate::buddy::Buddies;
+use crate::{
+  buddy::{Buddies, Buddy},
+  error::SquadError,
+  template::{DEFAULT_PLACEHOLDER, SectionFormat, Template},
+  trailer::{self, Trailer, TrailerConfig},
+  write,
+};
 
-// Markers for the git-squad section in the commit template
-const BEGIN_MARKER: &str = "# BEGIN GIT-SQUAD";
-const END_MARKER: &str = "# END GIT-SQUAD";
+/// Information about the current repository used to select buddy emails
+#[derive(Debug, Default)]
+pub struct RepoContext {
+  /// The root directory of the repository's working tree
+  pub path: Option<PathBuf>,
+  /// The urls of all configured remotes
+  pub remotes: Vec<String>,
+  /// Use GitHub noreply addresses for buddies with a GitHub account
+  pub noreply: bool,
+}
 
-pub fn get_commit_template_path() -> Result<PathBuf> {
-  let output = Command::new("git")
-    .args(["config", "--get", "commit.template"])
-    .output()
-    .context("Failed to execute git command")?;
+/// A commit as read by [`Repo::commits`]
+#[derive(Debug, Clone)]
+pub struct Commit {
+  pub sha: String,
+  pub author_email: String,
+  pub subject: String,
+  /// The full commit message
+  pub message: String,
+}
 
-  if !output.status.success() {
-    anyhow::bail!(
-      "No template file set. Configure one using `git config --set \
-       commit.template /path/to/template/file"
-    )
+/// A co-author trailer in the git-squad section that matches no buddy, e.g.
+/// added by hand or of a buddy removed from the buddies file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCoAuthor {
+  /// The trailer as written in the commit template
+  pub trailer: String,
+  co_author: Trailer,
+}
+
+impl UnknownCoAuthor {
+  /// The `Name <email>` part of the trailer
+  pub fn contact(&self) -> &str {
+    &self.co_author.value
   }
 
-  let path_str = String::from_utf8(output.stdout)
-    .context("Failed to parse git output")?
-    .trim()
-    .to_string();
+  /// The name of the co-author, if the trailer is well-formed
+  pub fn name(&self) -> Option<&str> {
+    let (name, _) = self.co_author.contact()?;
+    Some(name).filter(|name| !name.is_empty())
+  }
 
-  Ok(PathBuf::from(path_str))
+  /// The canonical email of the co-author, if the trailer is well-formed
+  pub fn email(&self) -> Option<String> {
+    let (_, email) = self.co_author.contact()?;
+    Some(trailer::canonical_email(email))
+  }
+
+  /// Checks if `query` is the email or the whole trailer of the co-author.
+  pub fn matches(&self, query: &str) -> bool {
+    let query = query.trim();
+    query.eq_ignore_ascii_case(&self.trailer)
+      || query.eq_ignore_ascii_case(self.contact())
+      || self
+        .email()
+        .is_some_and(|email| email == trailer::canonical_email(query))
+  }
 }
 
-pub fn get_active_buddies(buddies: &Buddies) -> Result<Buddies> {
-  let template_path = get_commit_template_path()?;
+/// The repository git-squad operates on. Git is run in `dir` if given and
+/// in the current directory otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct Repo {
+  dir: Option<PathBuf>,
+}
 
-  if !template_path.exists() {
-    return Ok(Buddies::default());
+impl Repo {
+  pub fn new(dir: Option<PathBuf>) -> Self {
+    Repo { dir }
   }
 
-  let mut file = File::open(&template_path)
-    .context("Failed to open commit template file")?;
+  /// The directory git is run in, `None` for the current directory
+  pub fn dir(&self) -> Option<&Path> {
+    self.dir.as_deref()
+  }
 
-  let mut contents = String::new();
-  file
-    .read_to_string(&mut contents)
-    .context("Failed to read commit template file")?;
+  pub fn context(&self) -> Result<RepoContext> {
+    let noreply = self.get_config_bool("squad.noreply")?;
 
-  let co_author_regex = Regex::new(r"Co-authored-by: .* <(.+)>").unwrap();
-  let mut active_buddies = Vec::new();
+    let output = self.git(&["rev-parse", "--show-toplevel"])?;
 
-  let mut in_squad_section = false;
-  for line in contents.lines() {
-    if line.trim() == BEGIN_MARKER {
-      in_squad_section = true;
-      continue;
+    // Outside of a repository there is nothing to match against
+    if !output.status.success() {
+      return Ok(RepoContext {
+        noreply,
+        ..RepoContext::default()
+      });
     }
-    if line.trim() == END_MARKER {
-      in_squad_section = false;
-      continue;
+
+    let path = String::from_utf8(output.stdout)
+      .context("Failed to parse git output")?
+      .trim()
+      .to_string();
+
+    let output = self.git(&["config", "--get-regexp", r"^remote\..*\.url$"])?;
+
+    let remotes = String::from_utf8(output.stdout)
+      .context("Failed to parse git output")?
+      .lines()
+      .filter_map(|line| line.split_once(' ').map(|(_, url)| url.to_string()))
+      .collect();
+
+    Ok(RepoContext {
+      path: Some(PathBuf::from(path)),
+      remotes,
+      noreply,
+    })
+  }
+
+  /// The value of `key` in the git config, `None` if it isn't set
+  pub fn get_config(&self, key: &str) -> Result<Option<String>> {
+    let output = self.git(&["config", "--get", key])?;
+
+    let value = String::from_utf8(output.stdout)
+      .context("Failed to parse git output")?
+      .trim()
+      .to_string();
+
+    Ok((!value.is_empty()).then_some(value))
+  }
+
+  /// The keys and values in the git config with a key matching `pattern`,
+  /// in the order git reads them. Values are kept as they are, including
+  /// trailing whitespace.
+  pub fn get_config_regexp(
+    &self,
+    pattern: &str,
+  ) -> Result<Vec<(String, String)>> {
+    let output = self.git(&["config", "--null", "--get-regexp", pattern])?;
+
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .split_terminator('\0')
+        .map(|entry| {
+          let (key, value) = entry.split_once('\n').unwrap_or((entry, ""));
+          (key.to_string(), value.to_string())
+        })
+        .collect(),
+    )
+  }
+
+  pub fn get_config_bool(&self, key: &str) -> Result<bool> {
+    let args = ["config", "--type=bool", "--get", key];
+    let output = self.git(&args)?;
+
+    // git config exits with 1 if the key is not set, other failures like an
+    // invalid boolean must not silently turn into false
+    match output.status.code() {
+      Some(0) => {}
+      Some(1) => return Ok(false),
+      _ => return Err(git_failure(&args, &output)),
     }
 
-    if in_squad_section {
-      if let Some(captures) = co_author_regex.captures(line) {
-        if let Some(email_match) = captures.get(1) {
-          let email = email_match.as_str();
-          if let Some(buddy) = buddies.get_buddy_by_email(email) {
-            active_buddies.push(buddy.clone());
-          }
-        }
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .trim()
+        == "true",
+    )
+  }
+
+  pub fn get_commit_template_path(&self) -> Result<PathBuf> {
+    let output = self.git(&["config", "--get", "commit.template"])?;
+
+    // git config exits with 1 if the key is not set
+    match output.status.code() {
+      Some(0) => {}
+      Some(1) => return Err(SquadError::NoTemplate.into()),
+      _ => {
+        return Err(git_failure(
+          &["config", "--get", "commit.template"],
+          &output,
+        ));
       }
     }
+
+    let path_str = String::from_utf8(output.stdout)
+      .context("Failed to parse git output")?
+      .trim()
+      .to_string();
+
+    Ok(self.resolve_template_path(&path_str))
+  }
+
+  /// Resolves the `commit.template` setting `path`. A relative template path
+  /// is relative to the directory git runs in.
+  pub fn resolve_template_path(&self, path: &str) -> PathBuf {
+    match &self.dir {
+      Some(dir) => dir.join(path),
+      None => PathBuf::from(path),
+    }
   }
 
-  Ok(Buddies::new(active_buddies))
-}
+  pub fn get_active_buddies(&self, buddies: &Buddies) -> Result<Buddies> {
+    let config = TrailerConfig::load(self)?;
+    let template = self.read_commit_template(&config)?;
 
-pub fn update_commit_template(active_buddies: &Buddies) -> Result<()> {
-  let template_path = get_commit_template_path()?;
+    let mut active_buddies = Vec::new();
 
-  if !template_path.exists() {
-    let template_dir = template_path.parent().unwrap();
-    if !template_dir.exists() {
-      anyhow::bail!("Template dir '{}' doens't exist", template_dir.display());
+    for line in template.co_authors() {
+      if let Some(co_author) = config.parse_line(line)
+        && let Some((_, email)) = co_author.contact()
+        && let Some(buddy) = buddies.get_buddy_by_email(email)
+        // Collapse duplicates left in the template by earlier runs
+        && !active_buddies.contains(buddy)
+      {
+        active_buddies.push(buddy.clone());
+      }
     }
-    File::create(&template_path)
-      .context("Failed to create commit template file")?;
+
+    Ok(Buddies::new(active_buddies))
   }
 
-  let mut file = File::open(&template_path)
-    .context("Failed to open commit template file")?;
+  /// The co-authors in the git-squad section that match none of `buddies`
+  pub fn get_unknown_co_authors(
+    &self,
+    buddies: &Buddies,
+  ) -> Result<Vec<UnknownCoAuthor>> {
+    let config = TrailerConfig::load(self)?;
+    Ok(unknown_co_authors(
+      &self.read_commit_template(&config)?,
+      buddies,
+      &config,
+    ))
+  }
 
-  let mut contents = String::new();
-  file
-    .read_to_string(&mut contents)
-    .context("Failed to read commit template file")?;
+  /// The aliases of the buddies that are removed after the next commit
+  pub fn get_one_shot_aliases(&self) -> Result<Vec<String>> {
+    let config = TrailerConfig::load(self)?;
+    Ok(
+      self
+        .read_commit_template(&config)?
+        .one_shot_aliases()
+        .to_vec(),
+    )
+  }
 
-  // Extract the content excluding our section
-  let mut new_content = String::new();
-  let mut skipping = false;
+  /// The format of the git-squad section that keeps it out of the messages
+  /// written with the commit template.
+  pub fn section_format(&self) -> Result<SectionFormat> {
+    // Only the default cleanup mode strips comments from edited messages
+    let strips_comments = matches!(
+      self.get_config("commit.cleanup")?.as_deref(),
+      None | Some("default" | "strip")
+    );
+    let comment = self
+      .get_config("core.commentChar")?
+      .unwrap_or_else(|| "#".to_string());
 
-  // Parse the file line by line, skipping our section
-  for line in contents.lines() {
-    if line.trim() == BEGIN_MARKER {
-      skipping = true;
-      continue;
+    // With `auto` git picks a comment character no line of the message
+    // starts with, which rules out the one of the markers
+    if !strips_comments || comment == "auto" {
+      return Ok(SectionFormat::Trailers);
     }
 
-    if line.trim() == END_MARKER {
-      skipping = false;
-      continue;
+    Ok(SectionFormat::Markers(comment))
+  }
+
+  /// Reads and checks the commit template, which is empty if it doesn't
+  /// exist yet.
+  fn read_commit_template(&self, config: &TrailerConfig) -> Result<Template> {
+    let template_path = self.get_commit_template_path()?;
+
+    if !template_path.exists() {
+      return Ok(Template::default());
+    }
+
+    let mut file = File::open(&template_path)
+      .context("Failed to open commit template file")?;
+
+    let mut contents = String::new();
+    file
+      .read_to_string(&mut contents)
+      .context("Failed to read commit template file")?;
+
+    Template::parse_checked(
+      &template_path,
+      &contents,
+      config,
+      &self.placeholder()?,
+    )
+  }
+
+  /// The line of the commit template the git-squad section is rendered at
+  pub fn placeholder(&self) -> Result<String> {
+    Ok(
+      self
+        .get_config("squad.placeholder")?
+        .unwrap_or_else(|| DEFAULT_PLACEHOLDER.to_string()),
+    )
+  }
+
+  /// The top level directory of the working tree
+  pub fn toplevel(&self) -> Result<PathBuf> {
+    self.rev_parse_path(&["rev-parse", "--show-toplevel"])
+  }
+
+  /// The git directory shared by all worktrees of the repository
+  pub fn git_common_dir(&self) -> Result<PathBuf> {
+    self.rev_parse_path(&["rev-parse", "--git-common-dir"])
+  }
+
+  /// The directory git runs hooks from, respecting `core.hooksPath`
+  pub fn hooks_dir(&self) -> Result<PathBuf> {
+    self.rev_parse_path(&["rev-parse", "--git-path", "hooks"])
+  }
+
+  /// The name of the checked out branch, `None` for a detached HEAD
+  pub fn current_branch(&self) -> Result<Option<String>> {
+    self.branch_name("HEAD")
+  }
+
+  /// The names of all branches pointing at `commit`
+  pub fn branches_at(&self, commit: &str) -> Result<Vec<String>> {
+    let args = [
+      "for-each-ref",
+      "--points-at",
+      commit,
+      "--format=%(refname:short)",
+      "refs/heads/",
+    ];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
+    }
+
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .lines()
+        .map(str::to_string)
+        .collect(),
+    )
+  }
+
+  fn branch_name(&self, rev: &str) -> Result<Option<String>> {
+    let output = self.git(&["rev-parse", "--symbolic-full-name", rev])?;
+
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .trim()
+        .strip_prefix("refs/heads/")
+        .map(str::to_string),
+    )
+  }
+
+  /// Runs a `git rev-parse` command printing a path, which is relative to the
+  /// directory git runs in.
+  fn rev_parse_path(&self, args: &[&str]) -> Result<PathBuf> {
+    let output = self.git(args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(args, &output));
+    }
+
+    let path = PathBuf::from(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .trim(),
+    );
+
+    Ok(match &self.dir {
+      Some(dir) => dir.join(path),
+      None => path,
+    })
+  }
+
+  /// The working trees of all initialized submodules, recursively
+  pub fn submodules(&self) -> Result<Vec<PathBuf>> {
+    let args = ["submodule", "foreach", "--quiet", "--recursive", "pwd"];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
+    }
+
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .lines()
+        .map(PathBuf::from)
+        .collect(),
+    )
+  }
+
+  /// The hash and the committer date in ISO 8601 format of the HEAD commit
+  pub fn head_commit(&self) -> Result<(String, String)> {
+    let args = ["show", "--no-patch", "--format=%H %cI", "HEAD"];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
+    }
+
+    let stdout =
+      String::from_utf8(output.stdout).context("Failed to parse git output")?;
+    let (sha, date) = stdout
+      .trim()
+      .split_once(' ')
+      .context("Failed to parse git output")?;
+
+    Ok((sha.to_string(), date.to_string()))
+  }
+
+  /// The message of the HEAD commit
+  pub fn head_message(&self) -> Result<String> {
+    let args = ["log", "-1", "--format=%B", "HEAD"];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
+    }
+
+    String::from_utf8(output.stdout).context("Failed to parse git output")
+  }
+
+  /// The commits in the revision `range`, oldest first
+  pub fn commits(&self, range: &str) -> Result<Vec<Commit>> {
+    let args = [
+      "log",
+      "--reverse",
+      "--format=%H%x00%ae%x00%s%x00%B%x1e",
+      range,
+      "--",
+    ];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
+    }
+
+    String::from_utf8(output.stdout)
+      .context("Failed to parse git output")?
+      .split('\x1e')
+      .map(str::trim_start)
+      .filter(|record| !record.is_empty())
+      .map(|record| {
+        let mut fields = record.splitn(4, '\0');
+        let mut next = || {
+          fields
+            .next()
+            .map(str::to_string)
+            .context("Failed to parse git output")
+        };
+
+        Ok(Commit {
+          sha: next()?,
+          author_email: next()?,
+          subject: next()?,
+          message: next()?,
+        })
+      })
+      .collect()
+  }
+
+  /// Maps `Name <email>` contacts to their canonical form with the mailmap
+  /// of the repository
+  pub fn check_mailmap(&self, contacts: &[&str]) -> Result<Vec<String>> {
+    if contacts.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let mut args = vec!["check-mailmap"];
+    args.extend(contacts);
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
     }
 
-    if !skipping {
-      new_content.push_str(line);
-      new_content.push('\n');
+    Ok(
+      String::from_utf8(output.stdout)
+        .context("Failed to parse git output")?
+        .lines()
+        .map(str::to_string)
+        .collect(),
+    )
+  }
+
+  /// The aliases of the buddies that are the user committing in the
+  /// repository, matching `user.email` directly or via the mailmap
+  pub fn self_aliases(&self, buddies: &Buddies) -> Result<Vec<String>> {
+    let Some(email) = self.get_config("user.email")? else {
+      return Ok(Vec::new());
+    };
+
+    let own = self.canonical_emails(&email)?;
+    let emails: Vec<&str> =
+      buddies.buddies.iter().flat_map(Buddy::all_emails).collect();
+    let same = self.same_person(&own, &emails)?;
+
+    Ok(
+      buddies
+        .buddies
+        .iter()
+        .filter(|b| {
+          own.iter().any(|e| b.has_email(e))
+            || b.all_emails().any(|e| same.contains(&e))
+        })
+        .map(|b| b.alias.clone())
+        .collect(),
+    )
+  }
+
+  /// The emails among `emails` that belong to the person with `email`,
+  /// directly or because the mailmap maps them to the same email
+  pub fn emails_of<'a>(
+    &self,
+    email: &str,
+    emails: &[&'a str],
+  ) -> Result<Vec<&'a str>> {
+    self.same_person(&self.canonical_emails(email)?, emails)
+  }
+
+  /// `email` and the email the mailmap maps it to, in canonical form
+  fn canonical_emails(&self, email: &str) -> Result<Vec<String>> {
+    let mut own = vec![trailer::canonical_email(email)];
+    let mapped = self.check_mailmap(&[&format!("<{email}>")])?;
+    own.extend(mapped.iter().filter_map(|c| contact_email(c)));
+    Ok(own)
+  }
+
+  /// The emails among `emails` that are one of the canonical emails `own`,
+  /// directly or via the mailmap
+  fn same_person<'a>(
+    &self,
+    own: &[String],
+    emails: &[&'a str],
+  ) -> Result<Vec<&'a str>> {
+    let contacts: Vec<String> =
+      emails.iter().map(|email| format!("<{email}>")).collect();
+    let mapped = self.check_mailmap(
+      &contacts.iter().map(String::as_str).collect::<Vec<_>>(),
+    )?;
+
+    Ok(
+      emails
+        .iter()
+        .zip(&mapped)
+        .filter(|(email, contact)| {
+          own.contains(&trailer::canonical_email(email))
+            || contact_email(contact).is_some_and(|e| own.contains(&e))
+        })
+        .map(|(email, _)| *email)
+        .collect(),
+    )
+  }
+
+  /// Sets `key` in the local config of the repository
+  pub fn set_config(&self, key: &str, value: &str) -> Result<()> {
+    let args = ["config", "--local", key, value];
+    let output = self.git(&args)?;
+
+    if !output.status.success() {
+      return Err(git_failure(&args, &output));
     }
+
+    Ok(())
   }
 
-  // Trim trailing whitespace
-  new_content = new_content.trim_end().to_string();
+  /// Replaces the git-squad section of the commit template with
+  /// `active_buddies`. The buddies with an alias in `one_shot` are marked to
+  /// be removed after the next commit, in addition to the ones that already
+  /// are. Co-authors that match none of `buddies` are kept, unless they match
+  /// one of the emails or trailers in `removed`. The section is written in
+  /// the current [`Repo::section_format`], migrating sections written in
+  /// another one. With `dry_run` a diff is printed instead.
+  pub fn update_commit_template(
+    &self,
+    buddies: &Buddies,
+    active_buddies: &Buddies,
+    one_shot: &[String],
+    removed: &[String],
+    repo: &RepoContext,
+    dry_run: bool,
+  ) -> Result<()> {
+    let template_path = self.get_commit_template_path()?;
+
+    let mut contents = String::new();
+    if template_path.exists() {
+      let mut file = File::open(&template_path)
+        .context("Failed to open commit template file")?;
+
+      file
+        .read_to_string(&mut contents)
+        .context("Failed to read commit template file")?;
+    } else if let Some(template_dir) = template_path
+      .parent()
+      // A template without directory is in the directory git runs in
+      .filter(|dir| !dir.as_os_str().is_empty())
+      && !template_dir.exists()
+    {
+      anyhow::bail!("Template dir '{}' doens't exist", template_dir.display());
+    }
+
+    let config = TrailerConfig::load(self)?;
+    let template = Template::parse_checked(
+      &template_path,
+      &contents,
+      &config,
+      &self.placeholder()?,
+    )?;
 
-  // Add our section with co-authors if needed
-  if !active_buddies.buddies.is_empty() {
-    new_content.push_str("\n\n");
-    new_content.push_str(BEGIN_MARKER);
-    new_content.push('\n');
+    let format = self.section_format()?;
 
+    let mut co_authors = Vec::new();
+    let mut emails = Vec::new();
     for buddy in &active_buddies.buddies {
-      new_content.push_str(&buddy.format_co_author());
-      new_content.push('\n');
+      // Hand-edited buddies files aren't validated when they are loaded
+      buddy.clone().validated().with_context(|| {
+        format!(
+          "Refusing to add invalid buddy '{}' to the commit template, check \
+           the buddies file with `git squad check-buddies`",
+          buddy.alias
+        )
+      })?;
+
+      // Buddies sharing an email would add the same co-author twice
+      let email = trailer::canonical_email(&buddy.email_for(repo));
+      if emails.contains(&email) {
+        continue;
+      }
+      emails.push(email);
+
+      co_authors.push(buddy.format_co_author(repo, &config));
+    }
+
+    for unknown in unknown_co_authors(&template, buddies, &config) {
+      if removed.iter().any(|query| unknown.matches(query))
+        || unknown.email().is_some_and(|email| emails.contains(&email))
+      {
+        continue;
+      }
+      co_authors.push(unknown.trailer);
+    }
+
+    let once: Vec<String> = active_buddies
+      .buddies
+      .iter()
+      .map(|buddy| buddy.alias.clone())
+      .filter(|alias| {
+        one_shot.contains(alias) || template.one_shot_aliases().contains(alias)
+      })
+      .collect();
+
+    if !once.is_empty() {
+      format.check_one_shot()?;
+    }
+
+    let new_content = template.render(&format, &co_authors, &once);
+
+    write::write_file(&template_path, &new_content, dry_run)
+      .context("Failed to write to commit template file")
+  }
+
+  /// Runs git with `args` in the directory of the repository. Only fails if
+  /// git can't be run at all, callers decide what a non-zero exit status
+  /// means.
+  fn git(&self, args: &[&str]) -> Result<Output> {
+    let mut command = Command::new("git");
+    if let Some(dir) = &self.dir {
+      command.arg("-C").arg(dir);
     }
 
-    new_content.push_str(END_MARKER);
-    new_content.push('\n');
+    command.args(args).output().map_err(|e| {
+      SquadError::Git {
+        command: args.join(" "),
+        message: e.to_string(),
+      }
+      .into()
+    })
+  }
+}
+
+fn git_failure(args: &[&str], output: &Output) -> anyhow::Error {
+  SquadError::Git {
+    command: args.join(" "),
+    message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
   }
+  .into()
+}
+
+/// The co-authors in the git-squad section of `template` that match none of
+/// `buddies`, without duplicates
+fn unknown_co_authors(
+  template: &Template,
+  buddies: &Buddies,
+  config: &TrailerConfig,
+) -> Vec<UnknownCoAuthor> {
+  let mut unknown: Vec<UnknownCoAuthor> = Vec::new();
+
+  for line in template.co_authors() {
+    let Some(trailer) = config.parse_line(line) else {
+      continue;
+    };
+    let co_author = UnknownCoAuthor {
+      trailer: line.to_string(),
+      co_author: trailer,
+    };
+    let email = co_author.email();
 
-  // Write back to file
-  let mut file = File::create(&template_path)
-    .context("Failed to open commit template file for writing")?;
+    if email
+      .as_ref()
+      .is_some_and(|email| buddies.get_buddy_by_email(email).is_some())
+      || unknown.iter().any(|u| match &email {
+        Some(email) => u.email().as_ref() == Some(email),
+        None => u.trailer == co_author.trailer,
+      })
+    {
+      continue;
+    }
 
-  file
-    .write_all(new_content.as_bytes())
-    .context("Failed to write to commit template file")?;
+    unknown.push(co_author);
+  }
+
+  unknown
+}
 
-  Ok(())
+/// The canonical email of a `Name <email>` contact as printed by
+/// `git check-mailmap`
+fn contact_email(contact: &str) -> Option<String> {
+  let (_, rest) = contact.rsplit_once('<')?;
+  let (email, _) = rest.split_once('>')?;
+  Some(trailer::canonical_email(email))
 }
@@ -0,0 +1,462 @@
+use std::{env, fs, path::Path};
+
+use anyhow::{Context, Result};
+
+use crate::{
+  buddy::Buddies,
+  config::{ConfigService, FileConfig},
+  error::SquadError,
+  git::Repo,
+  ledger, lint,
+  policy::Policy,
+  session::{self, Sessions},
+  trailer::TrailerConfig,
+  write,
+};
+
+// Identifies hook scripts written by git-squad
+const MARKER: &str = "# Installed by git-squad";
+
+/// The hooks git-squad installs
+pub const HOOKS: &[&str] = &[
+  "post-checkout",
+  "post-commit",
+  "prepare-commit-msg",
+  "commit-msg",
+];
+
+/// Installs `hooks` in `repo`, all [`HOOKS`] if empty. Existing hooks not
+/// installed by git-squad, e.g. by Gerrit or husky, are skipped with a
+/// warning unless `force` replaces them. The scripts call the current
+/// executable with the same buddies file.
+pub fn install(
+  repo: &Repo,
+  buddies_file: Option<&Path>,
+  hooks: &[String],
+  force: bool,
+  dry_run: bool,
+) -> Result<()> {
+  let hooks_dir = repo.hooks_dir()?;
+  let hooks = if hooks.is_empty() {
+    HOOKS.to_vec()
+  } else {
+    hooks.iter().map(String::as_str).collect()
+  };
+
+  // Decide on every hook before writing any of them
+  let (hooks, foreign): (Vec<_>, Vec<_>) = hooks
+    .into_iter()
+    .partition(|hook| force || !is_foreign_path(&hooks_dir.join(hook)));
+
+  for hook in foreign {
+    eprintln!(
+      "Warning: Skipped hook '{}', it was not installed by git-squad. Use \
+       --force to replace it",
+      hooks_dir.join(hook).display()
+    );
+  }
+
+  for hook in hooks {
+    install_hook(repo, buddies_file, hook, force, dry_run)?;
+  }
+
+  Ok(())
+}
+
+/// Installs a single `hook` in `repo`, see [`install`].
+pub fn install_hook(
+  repo: &Repo,
+  buddies_file: Option<&Path>,
+  hook: &str,
+  force: bool,
+  dry_run: bool,
+) -> Result<()> {
+  let hooks_dir = repo.hooks_dir()?;
+  let exe = env::current_exe().context("Failed to locate git-squad")?;
+  let path = hooks_dir.join(hook);
+
+  if !force && is_foreign_path(&path) {
+    anyhow::bail!(
+      "Hook '{}' was not installed by git-squad, use --force to replace it",
+      path.display()
+    );
+  }
+
+  if !dry_run {
+    fs::create_dir_all(&hooks_dir).with_context(|| {
+      format!("Failed to create directory '{}'", hooks_dir.display())
+    })?;
+  }
+
+  write::write_file(&path, &script(&exe, buddies_file, hook), dry_run)?;
+
+  if !dry_run {
+    make_executable(&path)?;
+  }
+  println!("Installed hook '{}'", path.display());
+
+  Ok(())
+}
+
+/// Removes all hooks installed by git-squad from `repo`.
+pub fn uninstall(repo: &Repo, dry_run: bool) -> Result<()> {
+  let hooks_dir = repo.hooks_dir()?;
+
+  for hook in HOOKS {
+    let path = hooks_dir.join(hook);
+
+    if path.exists() && is_ours(&path) {
+      if !dry_run {
+        fs::remove_file(&path)
+          .with_context(|| format!("Failed to remove '{}'", path.display()))?;
+      }
+      println!("Removed hook '{}'", path.display());
+    }
+  }
+
+  Ok(())
+}
+
+/// Checks if the git-squad version of `hook` is installed in `repo`.
+pub fn is_installed(repo: &Repo, hook: &str) -> bool {
+  repo
+    .hooks_dir()
+    .is_ok_and(|hooks_dir| is_ours(&hooks_dir.join(hook)))
+}
+
+/// Checks if `hook` in `repo` was installed by git-squad, but calls another
+/// executable or buddies file than an installation would now.
+pub fn is_outdated(
+  repo: &Repo,
+  buddies_file: Option<&Path>,
+  hook: &str,
+) -> Result<bool> {
+  let path = repo.hooks_dir()?.join(hook);
+  if !is_ours(&path) {
+    return Ok(false);
+  }
+
+  let exe = env::current_exe().context("Failed to locate git-squad")?;
+  let contents = fs::read_to_string(&path)
+    .with_context(|| format!("Failed to read '{}'", path.display()))?;
+
+  Ok(contents != script(&exe, buddies_file, hook))
+}
+
+/// Checks if `hook` exists in `repo`, but wasn't installed by git-squad.
+pub fn is_foreign(repo: &Repo, hook: &str) -> bool {
+  repo
+    .hooks_dir()
+    .is_ok_and(|hooks_dir| is_foreign_path(&hooks_dir.join(hook)))
+}
+
+fn is_foreign_path(path: &Path) -> bool {
+  path.exists() && !is_ours(path)
+}
+
+/// Runs `hook` with the arguments git passed to the hook script.
+pub fn run(
+  conf: &FileConfig,
+  repo: &Repo,
+  hook: &str,
+  args: &[String],
+  noreply: bool,
+  dry_run: bool,
+) -> Result<()> {
+  match hook {
+    "post-checkout" => post_checkout(conf, repo, args, noreply, dry_run),
+    "post-commit" => post_commit(conf, repo, noreply, dry_run),
+    "prepare-commit-msg" => prepare_commit_msg(repo, args, dry_run),
+    "commit-msg" => commit_msg(conf, repo, args, dry_run),
+    _ => anyhow::bail!("Unknown hook '{hook}'"),
+  }
+}
+
+/// Activates the session bound to the new branch after a branch checkout.
+fn post_checkout(
+  conf: &FileConfig,
+  repo: &Repo,
+  args: &[String],
+  noreply: bool,
+  dry_run: bool,
+) -> Result<()> {
+  // git passes the previous and the new HEAD, followed by 1 for branch
+  // checkouts and 0 for file checkouts
+  let [old_head, _new_head, flag] = args else {
+    anyhow::bail!("post-checkout expects 3 arguments, got {}", args.len());
+  };
+  if flag != "1" {
+    return Ok(());
+  }
+
+  let buddies = conf.load_buddies()?;
+  let current = match repo.get_active_buddies(&buddies) {
+    Ok(current) => current,
+    // Don't fail checkouts in repositories without a template
+    Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+      return Ok(());
+    }
+    Err(e) => return Err(e),
+  };
+
+  let mut sessions = Sessions::load(repo)?;
+  // HEAD is the new HEAD, by name unlike the commit git passes
+  let to = repo.current_branch()?;
+  let from =
+    previous_branch(repo, &sessions, old_head, to.as_deref(), &current)?;
+  if from == to {
+    return Ok(());
+  }
+
+  if let Some(aliases) =
+    sessions.switch(from.as_deref(), to.as_deref(), &current)
+  {
+    let session = session::buddies_for(&buddies, &aliases);
+    let mut context = repo.context()?;
+    context.noreply |= noreply;
+    repo.update_commit_template(
+      &buddies,
+      &session,
+      &[],
+      &[],
+      &context,
+      dry_run,
+    )?;
+
+    let names = if aliases.is_empty() {
+      "none".to_string()
+    } else {
+      aliases.join(", ")
+    };
+    println!("git-squad: active buddies: {names}");
+  }
+
+  sessions.save(repo, dry_run)
+}
+
+/// The branch left by a checkout from `old_head` to `to`. Branches at the
+/// same commit can't be told apart by the commit, so the branch `current` is
+/// bound to is preferred, then an unbound one other than `to`.
+fn previous_branch(
+  repo: &Repo,
+  sessions: &Sessions,
+  old_head: &str,
+  to: Option<&str>,
+  current: &Buddies,
+) -> Result<Option<String>> {
+  let mut branches = repo.branches_at(old_head)?;
+  branches.sort_by_key(|branch| Some(branch.as_str()) == to);
+
+  Ok(
+    branches
+      .iter()
+      .find(|branch| sessions.is_bound(branch, current))
+      .or_else(|| {
+        branches
+          .iter()
+          .find(|branch| sessions.get(branch).is_none())
+      })
+      .or(branches.first())
+      .cloned(),
+  )
+}
+
+/// Records the commit in the ledger and removes the one-shot buddies credited
+/// as co-authors of the commit from the session. Commits that didn't use the
+/// template, e.g. with `-m`, leave them for the next one.
+fn post_commit(
+  conf: &FileConfig,
+  repo: &Repo,
+  noreply: bool,
+  dry_run: bool,
+) -> Result<()> {
+  let buddies = conf.load_buddies()?;
+  let mut session = match repo.get_active_buddies(&buddies) {
+    Ok(session) => session,
+    // Don't fail commits in repositories without a template
+    Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+      return Ok(());
+    }
+    Err(e) => return Err(e),
+  };
+
+  ledger::record(repo, &session, dry_run)?;
+
+  let one_shot = repo.get_one_shot_aliases()?;
+  if one_shot.is_empty() {
+    return Ok(());
+  }
+
+  let config = TrailerConfig::load(repo)?;
+  let trailers = config.parse(&repo.head_message()?);
+  let credited = lint::co_author_emails(&trailers);
+  let removed: Vec<String> = session
+    .buddies
+    .iter()
+    .filter(|b| one_shot.contains(&b.alias))
+    .filter(|b| credited.iter().any(|email| b.has_email(email)))
+    .map(|b| b.alias.clone())
+    .collect();
+  if removed.is_empty() {
+    return Ok(());
+  }
+
+  session.buddies.retain(|b| !removed.contains(&b.alias));
+
+  let mut context = repo.context()?;
+  context.noreply |= noreply;
+  repo.update_commit_template(
+    &buddies,
+    &session,
+    &[],
+    &[],
+    &context,
+    dry_run,
+  )?;
+
+  println!(
+    "git-squad: removed one-shot buddies: {}",
+    removed.join(", ")
+  );
+
+  Ok(())
+}
+
+/// Collapses duplicate co-authors of messages git prepared, e.g. for
+/// amending or cherry-picking a commit that already has trailers.
+fn prepare_commit_msg(
+  repo: &Repo,
+  args: &[String],
+  dry_run: bool,
+) -> Result<()> {
+  let file = args.first().context("Missing commit message file")?;
+  dedupe_message_file(repo, Path::new(file), dry_run)
+}
+
+/// Collapses duplicate co-authors of the final message and rejects commits
+/// whose message violates the policy of the repository. Repositories without
+/// a policy accept every message.
+fn commit_msg(
+  conf: &FileConfig,
+  repo: &Repo,
+  args: &[String],
+  dry_run: bool,
+) -> Result<()> {
+  let file = args.first().context("Missing commit message file")?;
+  dedupe_message_file(repo, Path::new(file), dry_run)?;
+
+  if Policy::load(repo)?.is_none() {
+    return Ok(());
+  }
+
+  let problems = lint_message_file(conf, repo, Path::new(file))?;
+
+  if !problems.is_empty() {
+    println!("git-squad: the commit message violates the co-author policy:");
+    for problem in &problems {
+      println!("- {problem}");
+    }
+    anyhow::bail!("Commit rejected, fix the co-authors and try again");
+  }
+
+  Ok(())
+}
+
+fn dedupe_message_file(repo: &Repo, file: &Path, dry_run: bool) -> Result<()> {
+  let message = fs::read_to_string(file)
+    .with_context(|| format!("Failed to read '{}'", file.display()))?;
+  let (message, removed) =
+    TrailerConfig::load(repo)?.dedupe_co_authors(&message);
+
+  if removed.is_empty() {
+    return Ok(());
+  }
+
+  write::write_file(file, &message, dry_run)?;
+  for email in &removed {
+    println!("git-squad: removed duplicate co-author '{email}'");
+  }
+
+  Ok(())
+}
+
+/// Checks the commit message in `file` with the policy of `repo`, or the
+/// default policy if it has none.
+pub fn lint_message_file(
+  conf: &impl ConfigService,
+  repo: &Repo,
+  file: &Path,
+) -> Result<Vec<String>> {
+  let message = fs::read_to_string(file)
+    .with_context(|| format!("Failed to read '{}'", file.display()))?;
+  let policy = Policy::load(repo)?.unwrap_or_default();
+  let config = TrailerConfig::load(repo)?;
+
+  // The committer may be listed with another email the mailmap maps to them
+  let trailers = config.parse(&message);
+  let emails = lint::co_author_emails(&trailers);
+  let committer = match repo.get_config("user.email")? {
+    Some(email) => repo.emails_of(&email, &emails)?,
+    None => Vec::new(),
+  };
+
+  Ok(lint::lint_message(
+    &message,
+    &conf.load_buddies()?,
+    &policy,
+    &committer,
+    &config,
+  ))
+}
+
+fn script(exe: &Path, buddies_file: Option<&Path>, hook: &str) -> String {
+  let buddies_file = buddies_file
+    .map(|path| format!(" --buddies-file {}", shell_quote(path)))
+    .unwrap_or_default();
+
+  format!(
+    "#!/bin/sh\n{MARKER}, remove with `git squad hook uninstall`\nexec {}{buddies_file} hook run {hook} \"$@\"\n",
+    shell_quote(exe)
+  )
+}
+
+fn shell_quote(path: &Path) -> String {
+  format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
+
+fn is_ours(path: &Path) -> bool {
+  fs::read_to_string(path).is_ok_and(|contents| contents.contains(MARKER))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+
+  fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+    .with_context(|| format!("Failed to make '{}' executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_script() {
+    assert_eq!(
+      script(
+        Path::new("/usr/bin/git-squad"),
+        Some(Path::new("/home/peter/pan's buddies.toml")),
+        "post-checkout"
+      ),
+      "#!/bin/sh\n\
+       # Installed by git-squad, remove with `git squad hook uninstall`\n\
+       exec '/usr/bin/git-squad' --buddies-file '/home/peter/pan'\\''s \
+       buddies.toml' hook run post-checkout \"$@\"\n"
+    );
+  }
+}
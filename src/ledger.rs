@@ -0,0 +1,240 @@
+use std::{
+  fs::{self, OpenOptions},
+  io::Write,
+  path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{buddy::Buddies, git::Repo};
+
+const LEDGER_FILE: &str = "git-squad/ledger.jsonl";
+const SESSION_START_FILE: &str = "git-squad/session-start";
+
+/// A commit recorded by the `post-commit` hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+  pub sha: String,
+  /// The checked out branch, `None` for a detached HEAD
+  pub branch: Option<String>,
+  /// The committer date in ISO 8601 format
+  pub timestamp: String,
+  /// The aliases of the buddies active when the commit was made
+  pub buddies: Vec<String>,
+}
+
+/// Consecutive commits made with the same buddies
+#[derive(Debug, PartialEq, Eq)]
+pub struct LedgerSession<'a> {
+  pub buddies: &'a [String],
+  pub commits: Vec<&'a LedgerEntry>,
+}
+
+/// The commits recorded in a repository, one JSON object per line in the git
+/// directory shared by all worktrees
+pub fn path(repo: &Repo) -> Result<PathBuf> {
+  Ok(repo.git_common_dir()?.join(LEDGER_FILE))
+}
+
+/// Reads all recorded commits, oldest first. `None` if nothing was ever
+/// recorded.
+pub fn load(repo: &Repo) -> Result<Option<Vec<LedgerEntry>>> {
+  let path = path(repo)?;
+
+  if !path.exists() {
+    return Ok(None);
+  }
+
+  let contents = fs::read_to_string(&path)
+    .with_context(|| format!("Failed to read '{}'", path.display()))?;
+
+  contents
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .enumerate()
+    .map(|(index, line)| {
+      serde_json::from_str(line).with_context(|| {
+        format!("Failed to parse line {} of '{}'", index + 1, path.display())
+      })
+    })
+    .collect::<Result<_>>()
+    .map(Some)
+}
+
+/// Records the HEAD commit of `repo` as made with `session`. With `dry_run`
+/// the entry is printed instead.
+pub fn record(repo: &Repo, session: &Buddies, dry_run: bool) -> Result<()> {
+  let (sha, timestamp) = repo.head_commit()?;
+  let entry = LedgerEntry {
+    sha,
+    branch: repo.current_branch()?,
+    timestamp,
+    buddies: aliases(session),
+  };
+  let line =
+    serde_json::to_string(&entry).context("Failed to serialize commit")?;
+
+  if dry_run {
+    println!("{line}");
+    return Ok(());
+  }
+
+  let path = path(repo)?;
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).with_context(|| {
+      format!("Failed to create directory '{}'", dir.display())
+    })?;
+  }
+
+  let mut file = OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .with_context(|| format!("Failed to open '{}'", path.display()))?;
+  writeln!(file, "{line}")
+    .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// Marks the start of a new session if `session` has other buddies than
+/// `previous`. The new session only counts the commits recorded from now on,
+/// the marker is the number of commits recorded so far. Nothing is written
+/// with `dry_run`.
+pub fn start_session(
+  repo: &Repo,
+  previous: &Buddies,
+  session: &Buddies,
+  dry_run: bool,
+) -> Result<()> {
+  if dry_run || same_buddies(&aliases(previous), &aliases(session)) {
+    return Ok(());
+  }
+
+  let recorded = load(repo)?.map_or(0, |entries| entries.len());
+  let path = repo.git_common_dir()?.join(SESSION_START_FILE);
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).with_context(|| {
+      format!("Failed to create directory '{}'", dir.display())
+    })?;
+  }
+
+  fs::write(&path, format!("{recorded}\n"))
+    .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// The number of commits recorded before the current session started, 0 if
+/// no start was marked.
+pub fn session_start(repo: &Repo) -> Result<usize> {
+  let path = repo.git_common_dir()?.join(SESSION_START_FILE);
+
+  if !path.exists() {
+    return Ok(0);
+  }
+
+  fs::read_to_string(&path)
+    .with_context(|| format!("Failed to read '{}'", path.display()))?
+    .trim()
+    .parse()
+    .with_context(|| format!("Failed to parse '{}'", path.display()))
+}
+
+/// Groups `entries` into sessions of consecutive commits made with the same
+/// buddies, in any order.
+pub fn sessions(entries: &[LedgerEntry]) -> Vec<LedgerSession<'_>> {
+  let mut sessions: Vec<LedgerSession> = Vec::new();
+
+  for entry in entries {
+    match sessions.last_mut() {
+      Some(session) if same_buddies(session.buddies, &entry.buddies) => {
+        session.commits.push(entry);
+      }
+      _ => sessions.push(LedgerSession {
+        buddies: &entry.buddies,
+        commits: vec![entry],
+      }),
+    }
+  }
+
+  sessions
+}
+
+/// The number of commits made since `session` became active, counting back
+/// from the latest commit to the session `start`.
+pub fn commits_in_session(
+  entries: &[LedgerEntry],
+  start: usize,
+  session: &Buddies,
+) -> usize {
+  let aliases = aliases(session);
+
+  entries
+    .get(start..)
+    .unwrap_or_default()
+    .iter()
+    .rev()
+    .take_while(|entry| same_buddies(&entry.buddies, &aliases))
+    .count()
+}
+
+fn aliases(session: &Buddies) -> Vec<String> {
+  session.buddies.iter().map(|b| b.alias.clone()).collect()
+}
+
+fn same_buddies(a: &[String], b: &[String]) -> bool {
+  let mut a = a.to_vec();
+  let mut b = b.to_vec();
+  a.sort();
+  b.sort();
+  a == b
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::buddy::Buddy;
+
+  fn entry(sha: &str, buddies: &[&str]) -> LedgerEntry {
+    LedgerEntry {
+      sha: sha.to_string(),
+      branch: Some("main".to_string()),
+      timestamp: "2025-01-01T12:00:00+01:00".to_string(),
+      buddies: buddies.iter().map(ToString::to_string).collect(),
+    }
+  }
+
+  #[test]
+  fn test_sessions() {
+    let entries = vec![
+      entry("a", &["pp"]),
+      entry("b", &["pp", "wd"]),
+      entry("c", &["wd", "pp"]),
+      entry("d", &["pp"]),
+    ];
+
+    let sessions = sessions(&entries);
+    let shas: Vec<Vec<&str>> = sessions
+      .iter()
+      .map(|s| s.commits.iter().map(|c| c.sha.as_str()).collect())
+      .collect();
+
+    assert_eq!(shas, vec![vec!["a"], vec!["b", "c"], vec!["d"]]);
+  }
+
+  #[test]
+  fn test_commits_in_session() {
+    let entries = vec![entry("a", &["wd"]), entry("b", &["pp"])];
+    let session = Buddies::new(vec![Buddy {
+      alias: "pp".to_string(),
+      name: "Peter Pan".to_string(),
+      email: "peter.pan@example.com".to_string(),
+      emails: vec![],
+      github: None,
+    }]);
+
+    assert_eq!(commits_in_session(&entries, 0, &session), 1);
+    // Commits before the session started don't count
+    assert_eq!(commits_in_session(&entries, 2, &session), 0);
+    // The ledger was truncated by hand
+    assert_eq!(commits_in_session(&entries, 5, &session), 0);
+  }
+}
@@ -0,0 +1,201 @@
+use crate::{
+  buddy::{Buddies, co_author_trailer},
+  policy::Policy,
+  trailer::{Trailer, TrailerConfig},
+};
+
+/// Checks the co-author trailers of a commit `message` against the buddies
+/// and `policy`. `committer` are the co-author emails that belong to the
+/// person committing, who must not list themself, see
+/// [`crate::git::Repo::emails_of`]. Returns the problems found.
+pub fn lint_message(
+  message: &str,
+  buddies: &Buddies,
+  policy: &Policy,
+  committer: &[&str],
+  config: &TrailerConfig,
+) -> Vec<String> {
+  let mut problems = Vec::new();
+  let mut emails: Vec<String> = Vec::new();
+
+  for trailer in config.parse(message) {
+    if !trailer.is_co_author() {
+      continue;
+    }
+
+    let line = config.render(&trailer);
+    let Some((name, email)) = trailer.contact().filter(|(name, email)| {
+      trailer.token == config.co_author_token()
+        && !name.is_empty()
+        && email.contains('@')
+        && !email.contains(char::is_whitespace)
+    }) else {
+      problems.push(format!(
+        "Malformed co-author '{line}', expected '{}'",
+        co_author_trailer("Name", "email", config)
+      ));
+      continue;
+    };
+
+    if emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+      problems.push(format!("Duplicate co-author '{email}'"));
+      continue;
+    }
+    emails.push(email.to_string());
+
+    if committer.iter().any(|c| c.eq_ignore_ascii_case(email)) {
+      problems.push(format!("Co-author '{email}' is the committer"));
+    }
+
+    if !policy.allows_domain(email) {
+      problems.push(format!(
+        "Co-author '{email}' is not in an allowed domain ({})",
+        policy.allowed_domains.join(", ")
+      ));
+    }
+
+    if let Some(buddy) = buddies.get_buddy_by_email(email) {
+      if buddy.name != name {
+        problems.push(format!(
+          "Co-author '{line}' doesn't match buddy '{}', expected '{}'",
+          buddy.alias,
+          co_author_trailer(&buddy.name, email, config)
+        ));
+      }
+    } else if !policy.allow_unknown {
+      problems.push(format!("Unknown co-author '{email}'"));
+    }
+  }
+
+  if let Some(min) = policy.min_co_authors
+    && emails.len() < min
+  {
+    problems.push(format!(
+      "Expected at least {min} co-author(s), found {}",
+      emails.len()
+    ));
+  }
+
+  if let Some(max) = policy.max_co_authors
+    && emails.len() > max
+  {
+    problems.push(format!(
+      "Expected at most {max} co-author(s), found {}",
+      emails.len()
+    ));
+  }
+
+  problems
+}
+
+/// The emails of the co-authors in `trailers`
+pub fn co_author_emails(trailers: &[Trailer]) -> Vec<&str> {
+  trailers
+    .iter()
+    .filter(|trailer| trailer.is_co_author())
+    .filter_map(|trailer| Some(trailer.contact()?.1))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::buddy::Buddy;
+
+  fn buddies() -> Buddies {
+    Buddies::new(vec![Buddy {
+      alias: "pp".to_string(),
+      name: "Peter Pan".to_string(),
+      email: "peter.pan@example.com".to_string(),
+      emails: vec![],
+      github: None,
+    }])
+  }
+
+  #[test]
+  fn test_lint_message() {
+    let policy = Policy {
+      allowed_domains: vec!["example.com".to_string()],
+      min_co_authors: Some(1),
+      max_co_authors: Some(1),
+      allow_unknown: false,
+    };
+    let message = "Subject\n\n\
+                   Co-authored-by: Peter Pan <peter.pan@example.com>\n\
+                   co-authored-by: Peter Pan <peter.pan@example.com>\n\
+                   Co-authored-by: Peter <PETER.PAN@example.com>\n\
+                   Co-authored-by: Hook <hook@jolly-roger.sea>\n\
+                   Co-authored-by: Wendy Darling\n\
+                   # Co-authored-by: Ignored <comment@example.com>\n";
+
+    assert_eq!(
+      lint_message(
+        message,
+        &buddies(),
+        &policy,
+        &["hook@jolly-roger.sea"],
+        &TrailerConfig::default()
+      ),
+      vec![
+        "Malformed co-author 'co-authored-by: Peter Pan \
+         <peter.pan@example.com>', expected 'Co-authored-by: Name <email>'",
+        "Duplicate co-author 'PETER.PAN@example.com'",
+        "Co-author 'hook@jolly-roger.sea' is the committer",
+        "Co-author 'hook@jolly-roger.sea' is not in an allowed domain \
+         (example.com)",
+        "Unknown co-author 'hook@jolly-roger.sea'",
+        "Malformed co-author 'Co-authored-by: Wendy Darling', expected \
+         'Co-authored-by: Name <email>'",
+        "Expected at most 1 co-author(s), found 2",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lint_message_default_policy() {
+    let policy = Policy::default();
+
+    assert!(
+      lint_message(
+        "Subject\n\nCo-authored-by: Peter Pan <peter.pan@example.com>\n",
+        &buddies(),
+        &policy,
+        &[],
+        &TrailerConfig::default()
+      )
+      .is_empty()
+    );
+    assert_eq!(
+      lint_message(
+        "Subject\n\nCo-authored-by: Pan <peter.pan@example.com>\n",
+        &buddies(),
+        &policy,
+        &[],
+        &TrailerConfig::default()
+      ),
+      vec![
+        "Co-author 'Co-authored-by: Pan <peter.pan@example.com>' doesn't \
+         match buddy 'pp', expected 'Co-authored-by: Peter Pan \
+         <peter.pan@example.com>'"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lint_message_trailer_block() {
+    let policy = Policy::default();
+
+    // Only the trailer block counts, and trailers may be folded
+    assert!(
+      lint_message(
+        "Subject\n\nCo-authored-by: Pan <peter.pan@example.com>\nin the \
+         body\n\nCo-authored-by: Peter\n Pan <peter.pan@example.com>\n",
+        &buddies(),
+        &policy,
+        &[],
+        &TrailerConfig::default()
+      )
+      .is_empty()
+    );
+  }
+}
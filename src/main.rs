@@ -1,22 +1,56 @@
 mod buddy;
 mod cli;
 mod config;
+mod doctor;
+mod error;
 mod git;
+mod hook;
+mod ledger;
+mod lint;
+mod output;
+mod policy;
+mod prompt;
+mod resolve;
+mod session;
+mod template;
+mod trailer;
+mod validate;
+mod verify;
+mod workspace;
+mod write;
 
-use std::ffi::OsStr;
+use std::{
+  ffi::OsStr,
+  path::{self, Path},
+  process::ExitCode,
+};
 
-use anyhow::Result;
-use buddy::{Buddies, Buddy};
-use cli::{Cli, Command, print_completions};
+use anyhow::{Context, Result};
+use buddy::{Buddies, Buddy, GitHubAccount};
+use cli::{Cli, Command, HookCommand, print_completions};
 #[allow(deprecated)]
 use config::{ConfigService, DeprecatedFileConfig, FileConfig};
-use inquire::{MultiSelect, Text};
+use error::SquadError;
+use inquire::{Confirm, MultiSelect, Select, Text, validator::Validation};
 use nonempty::NonEmpty;
+use output::{BuddyRecord, OutputFormat};
+use resolve::Resolution;
+use session::Sessions;
 
-#[allow(clippy::too_many_lines)]
-fn main() -> Result<()> {
+fn main() -> ExitCode {
   let cli = Cli::new();
 
+  match run(&cli) {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(e) => {
+      eprintln!("{}", error::render(&e, cli.error_format));
+      ExitCode::from(error::exit_code(&e))
+    }
+  }
+}
+
+#[allow(clippy::too_many_lines)]
+fn run(cli: &Cli) -> Result<()> {
   if let Some(buddies_file) = &cli.buddies_file {
     let ext = buddies_file.extension();
     if ext == Some(OsStr::new("yaml")) || ext == Some(OsStr::new("yml")) {
@@ -29,7 +63,10 @@ fn main() -> Result<()> {
   } else {
     #[allow(deprecated)]
     let from = config::DeprecatedFileConfig { buddies_file: None };
-    let to = config::FileConfig { buddies_file: None };
+    let to = config::FileConfig {
+      buddies_file: None,
+      dry_run: cli.dry_run,
+    };
 
     #[allow(deprecated)]
     if from.get_buddies_file()?.exists() && !to.get_buddies_file()?.exists() {
@@ -40,16 +77,54 @@ fn main() -> Result<()> {
 
   let conf = config::FileConfig {
     buddies_file: cli.buddies_file.clone(),
+    dry_run: cli.dry_run,
   };
+  let repo = git::Repo::new(cli.repo.clone());
 
   match cli.get_command() {
-    Command::With { aliases } => {
+    Command::With {
+      aliases,
+      strict,
+      once,
+      all_repos,
+      recurse_submodules,
+      branch,
+    } => {
       let buddies = conf.load_buddies()?;
-      let mut active_buddies = git::get_active_buddies(&buddies)?;
+      let options = SessionOptions {
+        strict,
+        recurse_submodules,
+        branch,
+      };
+
+      if let Some(workspace) = all_repos {
+        let buddies_to_activate = resolve_workspace_buddies(
+          &buddies,
+          &aliases,
+          options.with_config(&repo)?.strict,
+        )?;
+
+        for_each_repo(&workspace, |repo| {
+          command_with(
+            repo,
+            cli,
+            &buddies,
+            &buddies_to_activate,
+            once,
+            options.with_config(repo)?,
+          )
+        })?;
+        return Ok(());
+      }
+
+      let options = options.with_config(&repo)?;
+      let active_buddies = repo.get_active_buddies(&buddies)?;
+      let self_aliases = repo.self_aliases(&buddies)?;
       let inactive_buddies = buddies
         .buddies
         .iter()
         .filter(|b| !active_buddies.buddies.contains(b))
+        .filter(|b| !self_aliases.contains(&b.alias))
         .collect();
 
       let buddies_to_activate = if aliases.is_empty() {
@@ -61,100 +136,160 @@ fn main() -> Result<()> {
           "add to the current session",
         )
       } else {
-        aliases
-          .iter()
-          .filter_map(|alias| {
-            buddies.get(alias).or_else(|| {
-              eprintln!("Buddy with alias '{alias}' does not exist");
-              None
-            })
-          })
-          .collect()
+        resolve_buddies(&buddies, &aliases, options.strict)?
       };
 
-      for buddy in buddies_to_activate {
-        active_buddies.add(buddy.clone()).map_or_else(
-          |_| eprintln!("Buddy '{}' is already active", buddy.alias),
-          |()| println!("Added buddy '{}' to the current session", buddy.alias),
-        );
-      }
-      git::update_commit_template(&active_buddies)?;
+      command_with(&repo, cli, &buddies, &buddies_to_activate, once, options)?;
     }
 
-    Command::Without { aliases } => {
+    Command::Without {
+      aliases,
+      strict,
+      all_repos,
+      recurse_submodules,
+      branch,
+    } => {
       let buddies = conf.load_buddies()?;
-      let mut active_buddies = git::get_active_buddies(&buddies)?;
-      let active_buddies_list = active_buddies.buddies.clone();
+      let options = SessionOptions {
+        strict,
+        recurse_submodules,
+        branch,
+      };
+
+      if let Some(workspace) = all_repos {
+        let buddies_to_deactivate = resolve_workspace_buddies(
+          &buddies,
+          &aliases,
+          options.with_config(&repo)?.strict,
+        )?;
+
+        for_each_repo(&workspace, |repo| {
+          command_without(
+            repo,
+            cli,
+            &buddies,
+            &buddies_to_deactivate,
+            &[],
+            options.with_config(repo)?,
+          )
+        })?;
+        return Ok(());
+      }
+
+      let options = options.with_config(&repo)?;
+      let active_buddies = repo.get_active_buddies(&buddies)?;
+
+      // Co-authors that aren't buddies are removed by email or trailer
+      let unknown = repo.get_unknown_co_authors(&buddies)?;
+      let (removed, aliases): (Vec<String>, Vec<String>) = aliases
+        .into_iter()
+        .partition(|alias| unknown.iter().any(|u| u.matches(alias)));
 
-      let buddies_to_deactivate = if aliases.is_empty() {
+      let buddies_to_deactivate = if !aliases.is_empty() {
+        resolve_buddies(&buddies, &aliases, options.strict)?
+      } else if !removed.is_empty() {
+        Vec::new()
+      } else {
         buddies_select(
-          NonEmpty::from_vec(active_buddies_list.iter().collect())
+          NonEmpty::from_vec(active_buddies.buddies.iter().collect())
             .map_or_else(
               || anyhow::bail!("No active buddies in the current session!"),
               Ok,
             )?,
           "remove from the current session",
         )
-      } else {
-        aliases
-          .iter()
-          .filter_map(|alias| {
-            buddies.get(alias).or_else(|| {
-              eprintln!("Buddy with alias '{alias}' does not exist");
-              None
-            })
-          })
-          .collect()
       };
 
-      for buddy in &buddies_to_deactivate {
-        active_buddies.forget(&buddy.alias).map_or_else(
-          |_| eprintln!("Buddy '{}' is not active", buddy.alias),
-          |()| {
-            println!(
-              "Removed buddy '{}' from the current session",
-              buddy.alias
-            );
-          },
-        );
-      }
-
-      git::update_commit_template(&active_buddies)?;
+      command_without(
+        &repo,
+        cli,
+        &buddies,
+        &buddies_to_deactivate,
+        &removed,
+        options,
+      )?;
     }
 
-    Command::Alone => {
-      git::update_commit_template(&Buddies::default())?;
-      println!("Removed all buddies from the current session");
+    Command::Alone {
+      all_repos,
+      recurse_submodules,
+      branch,
+    } => {
+      let buddies = conf.load_buddies()?;
+      let options = SessionOptions {
+        strict: false,
+        recurse_submodules,
+        branch,
+      };
+
+      if let Some(workspace) = all_repos {
+        for_each_repo(&workspace, |repo| {
+          command_alone(repo, cli, &buddies, options.with_config(repo)?)
+        })?;
+      } else {
+        command_alone(&repo, cli, &buddies, options.with_config(&repo)?)?;
+      }
     }
 
-    Command::Create { alias } => {
+    Command::Create {
+      alias,
+      github,
+      github_id,
+    } => {
       let mut buddies = conf.load_buddies()?;
 
       if buddies.has(&alias) {
-        anyhow::bail!("Buddy with alias '{}' already exists", alias)
+        return Err(SquadError::DuplicateAlias { alias }.into());
       }
 
-      let name =
-        Text::new(&format!("Enter name for buddy '{alias}':")).prompt()?;
-      let email =
-        Text::new(&format!("Enter email for buddy '{alias}': ")).prompt()?;
+      let name = Text::new(&format!("Enter name for buddy '{alias}':"))
+        .with_validator(|input: &str| {
+          Ok(validation_result(validate::validate_name(input)))
+        })
+        .prompt()?;
+      let email = Text::new(&format!("Enter email for buddy '{alias}': "))
+        .with_validator(|input: &str| {
+          Ok(validation_result(validate::normalize_email(input)))
+        })
+        .prompt()?;
 
       buddies.add(Buddy {
         alias: alias.clone(),
         name,
         email,
+        emails: vec![],
+        github: github.map(|login| GitHubAccount {
+          login,
+          id: github_id,
+        }),
       })?;
       conf.save_buddies(&buddies)?;
       println!("Created new buddy '{alias}'");
     }
 
+    Command::Adopt { email, alias } => {
+      command_adopt(&conf, &repo, email.as_deref(), alias.as_deref())?;
+    }
+
     Command::Forget { alias } => {
       let mut buddies = conf.load_buddies()?;
+      let Some(buddy) = buddy_to_forget(&buddies, &alias)? else {
+        println!("Kept all buddies");
+        return Ok(());
+      };
+      let alias = buddy.alias.clone();
 
-      let mut active_buddies = git::get_active_buddies(&buddies)?;
+      let mut active_buddies = repo.get_active_buddies(&buddies)?;
 
       let _ = active_buddies.forget(&alias);
-      git::update_commit_template(&active_buddies)?;
+      repo.update_commit_template(
+        &buddies,
+        &active_buddies,
+        &[],
+        &[],
+        &repo_context(&repo, cli)?,
+        cli.dry_run,
+      )?;
 
       buddies.forget(&alias)?;
       conf.save_buddies(&buddies)?;
@@ -163,13 +298,72 @@ fn main() -> Result<()> {
     }
 
     Command::Info => {
-      command_active(&conf)?;
-      command_list(&conf)?;
+      let context = repo_context(&repo, cli)?;
+      // Machine readable list output already contains the active state
+      if cli.output_format() == OutputFormat::Human {
+        command_active(&conf, &repo, &context, cli.output_format())?;
+      }
+      command_list(&conf, &repo, &context, cli.output_format())?;
+    }
+
+    Command::List => {
+      let context = repo_context(&repo, cli)?;
+      command_list(&conf, &repo, &context, cli.output_format())?;
+    }
+
+    Command::Active { all_repos } => {
+      if let Some(workspace) = all_repos {
+        if cli.output_format() != OutputFormat::Human {
+          anyhow::bail!("--all-repos only supports human readable output");
+        }
+
+        for_each_repo(&workspace, |repo| {
+          let context = repo_context(repo, cli)?;
+          command_active(&conf, repo, &context, cli.output_format())
+        })?;
+      } else {
+        let context = repo_context(&repo, cli)?;
+        command_active(&conf, &repo, &context, cli.output_format())?;
+      }
+    }
+
+    Command::Ledger => command_ledger(&repo, cli.output_format())?,
+
+    Command::Prompt { template } => {
+      print!("{}", prompt::render(&conf, &repo, &template, cli.noreply)?);
+    }
+
+    Command::CheckBuddies => {
+      command_check_buddies(&conf, cli.output_format())?;
+    }
+
+    Command::Doctor { fix } => {
+      doctor::run(&conf, &repo, cli.buddies_file.as_deref(), fix, cli.dry_run)?;
+    }
+
+    Command::LintMessage { file } => {
+      command_lint_message(&conf, &repo, &file, cli.output_format())?;
     }
 
-    Command::List => command_list(&conf)?,
+    Command::Verify {
+      range,
+      report,
+      mailmap,
+    } => {
+      let reports =
+        verify::verify(&repo, &conf.load_buddies()?, &range, mailmap)?;
+      print!("{}", verify::render(report, &range, &reports)?);
 
-    Command::Active => command_active(&conf)?,
+      let failures = reports.iter().filter(|r| !r.problems.is_empty()).count();
+      if failures > 0 {
+        anyhow::bail!(
+          "Found problems in {failures} of {} commit(s)",
+          reports.len()
+        );
+      }
+    }
+
+    Command::Hook { command } => command_hook(&conf, &repo, cli, command)?,
 
     Command::Completions { shell } => print_completions(shell)?,
 
@@ -186,10 +380,411 @@ fn main() -> Result<()> {
   Ok(())
 }
 
+fn repo_context(repo: &git::Repo, cli: &Cli) -> Result<git::RepoContext> {
+  let mut context = repo.context()?;
+  context.noreply |= cli.noreply;
+  Ok(context)
+}
+
+fn command_with(
+  repo: &git::Repo,
+  cli: &Cli,
+  buddies: &Buddies,
+  buddies_to_activate: &[&Buddy],
+  once: bool,
+  options: SessionOptions,
+) -> Result<()> {
+  if once {
+    repo.section_format()?.check_one_shot()?;
+  }
+
+  let mut active_buddies = repo.get_active_buddies(buddies)?;
+
+  if options.strict
+    && let Some(buddy) = buddies_to_activate
+      .iter()
+      .find(|b| active_buddies.has(&b.alias))
+  {
+    return Err(
+      SquadError::AlreadyActive {
+        alias: buddy.alias.clone(),
+      }
+      .into(),
+    );
+  }
+
+  let self_aliases = repo.self_aliases(buddies)?;
+  let mut one_shot = Vec::new();
+  for buddy in buddies_to_activate {
+    if self_aliases.contains(&buddy.alias) {
+      eprintln!(
+        "Warning: Skipping buddy '{}', that's you. You can't be your own \
+         co-author",
+        buddy.alias
+      );
+      continue;
+    }
+
+    if active_buddies.has(&buddy.alias) {
+      eprintln!(
+        "{}",
+        SquadError::AlreadyActive {
+          alias: buddy.alias.clone(),
+        }
+      );
+      continue;
+    }
+
+    active_buddies.buddies.push((*buddy).clone());
+    if once {
+      one_shot.push(buddy.alias.clone());
+      println!("Added buddy '{}' for the next commit", buddy.alias);
+    } else {
+      println!("Added buddy '{}' to the current session", buddy.alias);
+    }
+  }
+
+  update_session(repo, cli, buddies, &active_buddies, &one_shot, &[], options)
+}
+
+fn command_without(
+  repo: &git::Repo,
+  cli: &Cli,
+  buddies: &Buddies,
+  buddies_to_deactivate: &[&Buddy],
+  removed: &[String],
+  options: SessionOptions,
+) -> Result<()> {
+  let mut active_buddies = repo.get_active_buddies(buddies)?;
+
+  if options.strict
+    && let Some(buddy) = buddies_to_deactivate
+      .iter()
+      .find(|b| !active_buddies.has(&b.alias))
+  {
+    return Err(
+      SquadError::NotActive {
+        alias: buddy.alias.clone(),
+      }
+      .into(),
+    );
+  }
+
+  for buddy in buddies_to_deactivate {
+    active_buddies.forget(&buddy.alias).map_or_else(
+      |_| {
+        eprintln!(
+          "{}",
+          SquadError::NotActive {
+            alias: buddy.alias.clone(),
+          }
+        );
+      },
+      |()| {
+        println!("Removed buddy '{}' from the current session", buddy.alias);
+      },
+    );
+  }
+
+  for query in removed {
+    println!("Removed unknown co-author '{query}' from the current session");
+  }
+
+  update_session(repo, cli, buddies, &active_buddies, &[], removed, options)
+}
+
+fn command_alone(
+  repo: &git::Repo,
+  cli: &Cli,
+  buddies: &Buddies,
+  options: SessionOptions,
+) -> Result<()> {
+  update_session(repo, cli, buddies, &Buddies::default(), &[], &[], options)?;
+  println!("Removed all buddies from the current session");
+
+  // Only removed explicitly, they can't be added back by alias
+  for unknown in repo.get_unknown_co_authors(buddies)? {
+    println!(
+      "Kept unknown co-author '{}', remove it with `git squad without \
+       \"{}\"`",
+      unknown.contact(),
+      unknown.email().unwrap_or_else(|| unknown.trailer.clone())
+    );
+  }
+
+  Ok(())
+}
+
+/// How a change of the session is applied
+#[derive(Debug, Clone, Copy)]
+struct SessionOptions {
+  /// Fail instead of skipping unknown, active or inactive buddies
+  strict: bool,
+  /// Also update all initialized submodules
+  recurse_submodules: bool,
+  /// Bind the resulting session to the current branch
+  branch: bool,
+}
+
+impl SessionOptions {
+  /// Enables the options that are set in the git config of `repo`.
+  fn with_config(self, repo: &git::Repo) -> Result<Self> {
+    Ok(SessionOptions {
+      strict: self.strict || repo.get_config_bool("squad.strict")?,
+      recurse_submodules: self.recurse_submodules
+        || repo.get_config_bool("squad.recurseSubmodules")?,
+      ..self
+    })
+  }
+}
+
+/// Writes `session` to the commit template of `repo` and, depending on
+/// `options`, of all its initialized submodules and binds it to the current
+/// branch.
+fn update_session(
+  repo: &git::Repo,
+  cli: &Cli,
+  buddies: &Buddies,
+  session: &Buddies,
+  one_shot: &[String],
+  removed: &[String],
+  options: SessionOptions,
+) -> Result<()> {
+  let previous = repo.get_active_buddies(buddies)?;
+
+  repo.update_commit_template(
+    buddies,
+    session,
+    one_shot,
+    removed,
+    &repo_context(repo, cli)?,
+    cli.dry_run,
+  )?;
+
+  ledger::start_session(repo, &previous, session, cli.dry_run)?;
+
+  if !one_shot.is_empty() {
+    ensure_post_commit_hook(repo, cli);
+  }
+
+  if options.recurse_submodules {
+    sync_submodules(repo, cli, buddies, session, one_shot, removed)?;
+  }
+
+  if options.branch {
+    let Some(branch) = repo.current_branch()? else {
+      anyhow::bail!("Can't bind the session, no branch is checked out");
+    };
+
+    // One-shot buddies are not part of the branch's session
+    let persistent = without_one_shot(session, one_shot);
+
+    let mut sessions = Sessions::load(repo)?;
+    sessions.bind(&branch, &persistent);
+    sessions.save(repo, cli.dry_run)?;
+    println!("Bound the session to branch '{branch}'");
+
+    if !hook::is_installed(repo, "post-checkout") {
+      println!(
+        "Run `git squad hook install` to switch sessions on checkout \
+         automatically"
+      );
+    }
+  }
+
+  Ok(())
+}
+
+/// Installs the `post-commit` hook that removes one-shot buddies if it isn't
+/// installed yet. Only warns if that fails, the session was updated already.
+fn ensure_post_commit_hook(repo: &git::Repo, cli: &Cli) {
+  if hook::is_installed(repo, "post-commit") {
+    return;
+  }
+
+  if let Err(e) = hook::install_hook(
+    repo,
+    cli.buddies_file.as_deref(),
+    "post-commit",
+    false,
+    cli.dry_run,
+  ) {
+    eprintln!(
+      "Warning: {e}. One-shot buddies won't be removed after the next commit"
+    );
+  }
+}
+
+/// Makes the sessions of all initialized submodules of `repo` match
+/// `session`. Submodules without a commit template are configured to share
+/// the template of `repo`.
+fn sync_submodules(
+  repo: &git::Repo,
+  cli: &Cli,
+  buddies: &Buddies,
+  session: &Buddies,
+  one_shot: &[String],
+  removed: &[String],
+) -> Result<()> {
+  let template = path::absolute(repo.get_commit_template_path()?)
+    .context("Failed to resolve commit template path")?;
+
+  for dir in repo.submodules()? {
+    let submodule = git::Repo::new(Some(dir.clone()));
+
+    match submodule.get_commit_template_path() {
+      Ok(path) if path::absolute(&path)? == template => {}
+      Ok(_) => {
+        submodule.update_commit_template(
+          buddies,
+          session,
+          one_shot,
+          removed,
+          &repo_context(&submodule, cli)?,
+          cli.dry_run,
+        )?;
+        println!("Updated submodule '{}'", dir.display());
+
+        if !one_shot.is_empty() {
+          ensure_post_commit_hook(&submodule, cli);
+        }
+      }
+      Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+        if !cli.dry_run {
+          submodule
+            .set_config("commit.template", &template.to_string_lossy())?;
+        }
+        println!(
+          "Configured submodule '{}' to use '{}'",
+          dir.display(),
+          template.display()
+        );
+      }
+      Err(e) => return Err(e),
+    }
+  }
+
+  Ok(())
+}
+
+/// Runs `command` in every repository of `workspace`, a directory containing
+/// repositories or a file listing them, and prints a summary. Repositories
+/// without a commit template are skipped, any other error fails the whole
+/// command after all repositories were processed.
+fn for_each_repo(
+  workspace: &Path,
+  command: impl Fn(&git::Repo) -> Result<()>,
+) -> Result<()> {
+  let repos = workspace::discover(workspace)?;
+  if repos.is_empty() {
+    anyhow::bail!("No git repositories found in '{}'", workspace.display());
+  }
+
+  let mut summary = Vec::new();
+  let mut failed = 0;
+
+  for workspace_repo in &repos {
+    println!("{}:", workspace_repo.name);
+
+    let status = if workspace_repo.path.is_dir() {
+      match command(&git::Repo::new(Some(workspace_repo.path.clone()))) {
+        Ok(()) => "ok".to_string(),
+        Err(e) if matches!(e.downcast_ref(), Some(SquadError::NoTemplate)) => {
+          "skipped, no commit template configured".to_string()
+        }
+        Err(e) => {
+          failed += 1;
+          format!("failed, {e}")
+        }
+      }
+    } else {
+      failed += 1;
+      "failed, directory doesn't exist".to_string()
+    };
+
+    println!();
+    summary.push((&workspace_repo.name, status));
+  }
+
+  println!("Summary:");
+  for (name, status) in summary {
+    println!("- {name}: {status}");
+  }
+
+  if failed > 0 {
+    anyhow::bail!("Failed in {failed} of {} repositories", repos.len())
+  }
+
+  Ok(())
+}
+
+/// Resolves the `aliases` for `--all-repos` where there is no single session
+/// to choose buddies from interactively.
+fn resolve_workspace_buddies<'a>(
+  buddies: &'a Buddies,
+  aliases: &[String],
+  strict: bool,
+) -> Result<Vec<&'a Buddy>> {
+  if aliases.is_empty() {
+    anyhow::bail!("Aliases are required with --all-repos");
+  }
+
+  resolve_buddies(buddies, aliases, strict)
+}
+
+fn command_hook(
+  conf: &FileConfig,
+  repo: &git::Repo,
+  cli: &Cli,
+  command: HookCommand,
+) -> Result<()> {
+  match command {
+    HookCommand::Install {
+      force,
+      hooks,
+      recurse_submodules,
+    } => {
+      let buddies_file = cli.buddies_file.as_deref();
+      hook::install(repo, buddies_file, &hooks, force, cli.dry_run)?;
+
+      if recurse_submodules
+        || repo.get_config_bool("squad.recurseSubmodules")?
+      {
+        for dir in repo.submodules()? {
+          let submodule = git::Repo::new(Some(dir));
+          hook::install(&submodule, buddies_file, &hooks, force, cli.dry_run)?;
+        }
+      }
+    }
+
+    HookCommand::Uninstall { recurse_submodules } => {
+      hook::uninstall(repo, cli.dry_run)?;
+
+      if recurse_submodules
+        || repo.get_config_bool("squad.recurseSubmodules")?
+      {
+        for dir in repo.submodules()? {
+          hook::uninstall(&git::Repo::new(Some(dir)), cli.dry_run)?;
+        }
+      }
+    }
+
+    HookCommand::Run { hook, args } => {
+      hook::run(conf, repo, &hook, &args, cli.noreply, cli.dry_run)?;
+    }
+  }
+
+  Ok(())
+}
+
 #[allow(deprecated)]
 fn migrate_config(from: &DeprecatedFileConfig, to: &FileConfig) -> Result<()> {
   let old = from.get_buddies_file()?;
-  println!("Migrating {:#?} to {:#?}", old, to.get_buddies_file()?);
+  println!(
+    "Migrating {} to {}",
+    old.display(),
+    to.get_buddies_file()?.display()
+  );
 
   from.migrate(to)?;
 
@@ -198,9 +793,28 @@ fn migrate_config(from: &DeprecatedFileConfig, to: &FileConfig) -> Result<()> {
   Ok(())
 }
 
-fn command_list(conf: &impl ConfigService) -> Result<()> {
+fn command_list(
+  conf: &impl ConfigService,
+  repo: &git::Repo,
+  context: &git::RepoContext,
+  format: OutputFormat,
+) -> Result<()> {
   let buddies = conf.load_buddies()?;
 
+  if let Some(format) = format.machine() {
+    // Listing buddies also works without a configured commit template
+    let active_buddies = repo.get_active_buddies(&buddies).unwrap_or_default();
+    let records: Vec<_> = buddies
+      .buddies
+      .iter()
+      .map(|buddy| {
+        BuddyRecord::new(buddy, context, active_buddies.has(&buddy.alias))
+      })
+      .collect();
+    print!("{}", output::render_buddies(format, &records)?);
+    return Ok(());
+  }
+
   if buddies.buddies.is_empty() {
     println!("No buddies found.");
     return Ok(());
@@ -214,35 +828,400 @@ fn command_list(conf: &impl ConfigService) -> Result<()> {
   Ok(())
 }
 
-fn command_active(conf: &impl ConfigService) -> Result<()> {
+/// Adds the unknown co-authors of the session, or the one with `email`, as
+/// buddies. Aliases are prompted for unless `alias` is given.
+fn command_adopt(
+  conf: &impl ConfigService,
+  repo: &git::Repo,
+  email: Option<&str>,
+  alias: Option<&str>,
+) -> Result<()> {
+  let mut buddies = conf.load_buddies()?;
+  let mut unknown = repo.get_unknown_co_authors(&buddies)?;
+
+  if let Some(email) = email {
+    unknown.retain(|co_author| co_author.matches(email));
+    if unknown.is_empty() {
+      anyhow::bail!("No unknown co-author '{email}' in the current session");
+    }
+  } else if unknown.is_empty() {
+    println!("No unknown co-authors in the current session.");
+    return Ok(());
+  }
+
+  let mut adopted = 0;
+  for co_author in &unknown {
+    let (Some(name), Some(email)) = (co_author.name(), co_author.email())
+    else {
+      eprintln!(
+        "Warning: Skipping malformed co-author '{}'",
+        co_author.trailer
+      );
+      continue;
+    };
+
+    let alias = match alias {
+      Some(alias) => alias.to_string(),
+      None => Text::new(&format!(
+        "Enter alias for {} (leave empty to skip):",
+        co_author.contact()
+      ))
+      .with_validator(|input: &str| {
+        Ok(if input.is_empty() {
+          Validation::Valid
+        } else {
+          validation_result(validate::validate_alias(input))
+        })
+      })
+      .prompt()?,
+    };
+    if alias.is_empty() {
+      continue;
+    }
+
+    buddies.add(Buddy {
+      alias: alias.clone(),
+      name: name.to_string(),
+      email,
+      emails: vec![],
+      github: None,
+    })?;
+    println!("Adopted '{}' as buddy '{alias}'", co_author.contact());
+    adopted += 1;
+  }
+
+  if adopted > 0 {
+    conf.save_buddies(&buddies)?;
+  }
+
+  Ok(())
+}
+
+fn command_check_buddies(
+  conf: &impl ConfigService,
+  format: OutputFormat,
+) -> Result<()> {
+  let buddies = conf.load_buddies()?;
+  let problems = buddies.problems();
+
+  if let Some(format) = format.machine() {
+    print!("{}", output::render_messages(format, &problems)?);
+  } else if problems.is_empty() {
+    println!("No problems found.");
+  } else {
+    println!("Problems found in buddies file:");
+    for problem in &problems {
+      println!("- {problem}");
+    }
+  }
+
+  if !problems.is_empty() {
+    anyhow::bail!("Found {} problem(s) in buddies file", problems.len())
+  }
+
+  Ok(())
+}
+
+fn command_active(
+  conf: &impl ConfigService,
+  repo: &git::Repo,
+  context: &git::RepoContext,
+  format: OutputFormat,
+) -> Result<()> {
   let buddies = conf.load_buddies()?;
-  let active_buddies = git::get_active_buddies(&buddies)?;
+  let active_buddies = repo.get_active_buddies(&buddies)?;
+
+  let unknown = repo.get_unknown_co_authors(&buddies)?;
 
-  if active_buddies.buddies.is_empty() {
-    println!("No active buddies in the current session.");
+  if let Some(format) = format.machine() {
+    let records: Vec<_> = active_buddies
+      .buddies
+      .iter()
+      .map(|buddy| BuddyRecord::new(buddy, context, true))
+      .chain(unknown.iter().map(BuddyRecord::unknown))
+      .collect();
+    print!("{}", output::render_buddies(format, &records)?);
     return Ok(());
   }
 
-  println!("Active buddies in the current session:");
-  for buddy in &active_buddies.buddies {
-    println!("- {} ({} <{}>)", buddy.alias, buddy.name, buddy.email);
+  let one_shot = repo.get_one_shot_aliases()?;
+  let persistent = without_one_shot(&active_buddies, &one_shot);
+  let binding = bound_branch(repo, &persistent)
+    .map(|branch| format!(" (bound to branch '{branch}')"))
+    .unwrap_or_default();
+
+  // Only known if the post-commit hook records commits
+  let start = ledger::session_start(repo)?;
+  let commits = ledger::load(repo)?.map(|entries| {
+    ledger::commits_in_session(&entries, start, &active_buddies)
+  });
+
+  if active_buddies.buddies.is_empty() && unknown.is_empty() {
+    println!("No active buddies in the current session{binding}.");
+  } else {
+    println!("Active buddies in the current session{binding}:");
+    for buddy in &active_buddies.buddies {
+      let once = if one_shot.contains(&buddy.alias) {
+        " (next commit only)"
+      } else {
+        ""
+      };
+      println!(
+        "- {} ({} <{}>){once}",
+        buddy.alias,
+        buddy.name,
+        buddy.email_for(context)
+      );
+    }
+    for co_author in &unknown {
+      println!("- unknown ({})", co_author.contact());
+    }
+  }
+
+  if !unknown.is_empty() {
+    println!("Run `git squad adopt` to add unknown co-authors as buddies.");
+  }
+
+  if let Some(commits) = commits {
+    println!("{} made in the current session.", commit_count(commits));
   }
 
   Ok(())
 }
 
+/// Checks the co-authors of the commit message in `file`, failing if it has
+/// problems
+fn command_lint_message(
+  conf: &impl ConfigService,
+  repo: &git::Repo,
+  file: &Path,
+  format: OutputFormat,
+) -> Result<()> {
+  let problems = hook::lint_message_file(conf, repo, file)?;
+
+  if let Some(format) = format.machine() {
+    print!("{}", output::render_messages(format, &problems)?);
+  } else if problems.is_empty() {
+    println!("No problems found.");
+  } else {
+    println!("Problems found in commit message:");
+    for problem in &problems {
+      println!("- {problem}");
+    }
+  }
+
+  if !problems.is_empty() {
+    anyhow::bail!("Found {} problem(s) in commit message", problems.len())
+  }
+
+  Ok(())
+}
+
+fn command_ledger(repo: &git::Repo, format: OutputFormat) -> Result<()> {
+  let entries = ledger::load(repo)?;
+
+  if let Some(format) = format.machine() {
+    print!(
+      "{}",
+      output::render_ledger(format, entries.as_deref().unwrap_or_default())?
+    );
+    return Ok(());
+  }
+
+  let Some(entries) = entries.filter(|entries| !entries.is_empty()) else {
+    println!(
+      "No commits recorded yet. Run `git squad hook install` to record \
+       commits."
+    );
+    return Ok(());
+  };
+
+  for (index, session) in ledger::sessions(&entries).iter().enumerate() {
+    if index > 0 {
+      println!();
+    }
+
+    let buddies = if session.buddies.is_empty() {
+      "Session without buddies".to_string()
+    } else {
+      format!("Session with {}", session.buddies.join(", "))
+    };
+    println!("{buddies} ({}):", commit_count(session.commits.len()));
+
+    for commit in &session.commits {
+      let branch = commit
+        .branch
+        .as_ref()
+        .map(|branch| format!(" on {branch}"))
+        .unwrap_or_default();
+      let short = commit.sha.get(..7).unwrap_or(&commit.sha);
+      println!("- {short} {}{branch}", commit.timestamp);
+    }
+  }
+
+  Ok(())
+}
+
+fn commit_count(count: usize) -> String {
+  if count == 1 {
+    "1 commit".to_string()
+  } else {
+    format!("{count} commits")
+  }
+}
+
+/// The buddies of `session` that stay after the next commit
+fn without_one_shot(session: &Buddies, one_shot: &[String]) -> Buddies {
+  Buddies::new(
+    session
+      .buddies
+      .iter()
+      .filter(|b| !one_shot.contains(&b.alias))
+      .cloned()
+      .collect(),
+  )
+}
+
+/// The current branch if `active_buddies` is the session bound to it
+fn bound_branch(repo: &git::Repo, active_buddies: &Buddies) -> Option<String> {
+  let branch = repo.current_branch().ok()??;
+  let sessions = Sessions::load(repo).ok()?;
+
+  sessions.is_bound(&branch, active_buddies).then_some(branch)
+}
+
 fn buddies_select<'a>(
   buddies: NonEmpty<&'a Buddy>,
   purpose: &str,
 ) -> Vec<&'a Buddy> {
   let buddies_vec: Vec<&Buddy> = buddies.into_iter().collect();
 
-  let selections = MultiSelect::new(
+  MultiSelect::new(
     &format!("Choose one or more buddies to {purpose}"),
     buddies_vec,
   )
   .prompt()
-  .unwrap_or_else(|_| vec![]);
+  .unwrap_or_else(|_| vec![])
+}
+
+/// Resolves `query` to a buddy, asking the user to choose if several buddies
+/// match. The error lists similar buddies if none does.
+fn resolve_buddy<'a>(buddies: &'a Buddies, query: &str) -> Result<&'a Buddy> {
+  match resolve::resolve(buddies, query) {
+    Resolution::Found(buddy) => Ok(buddy),
+    Resolution::Ambiguous(candidates) => {
+      let aliases = candidates.iter().map(|b| b.alias.clone()).collect();
+
+      Select::new(
+        &format!("Several buddies match '{query}', choose one"),
+        candidates,
+      )
+      .prompt()
+      .map_err(|_| {
+        SquadError::Ambiguous {
+          query: query.to_string(),
+          aliases,
+        }
+        .into()
+      })
+    }
+    Resolution::NotFound(suggestions) => Err(
+      SquadError::NotFound {
+        query: query.to_string(),
+        suggestions: suggestions
+          .iter()
+          .map(|buddy| format!("{} ({buddy})", buddy.alias))
+          .collect(),
+      }
+      .into(),
+    ),
+  }
+}
 
-  selections
+/// Finds the buddy with exactly `alias` as alias or email. Used by strict
+/// mode, where a fuzzy match is only offered as a suggestion.
+fn exact_buddy<'a>(buddies: &'a Buddies, alias: &str) -> Result<&'a Buddy> {
+  if let Some(buddy) = buddies
+    .get(alias)
+    .or_else(|| buddies.get_buddy_by_email(alias))
+  {
+    return Ok(buddy);
+  }
+
+  let suggestions = match resolve::resolve(buddies, alias) {
+    Resolution::Found(buddy) => vec![buddy],
+    Resolution::Ambiguous(candidates) => candidates,
+    Resolution::NotFound(suggestions) => suggestions,
+  };
+
+  Err(
+    SquadError::NotFound {
+      query: alias.to_string(),
+      suggestions: suggestions
+        .iter()
+        .map(|buddy| format!("{} ({buddy})", buddy.alias))
+        .collect(),
+    }
+    .into(),
+  )
+}
+
+/// Resolves `query` to the buddy to forget, `None` if the user declines.
+/// Anything but the exact alias has to be confirmed, so that a typo doesn't
+/// delete another buddy. Without a terminal to confirm on, the match is only
+/// suggested.
+fn buddy_to_forget<'a>(
+  buddies: &'a Buddies,
+  query: &str,
+) -> Result<Option<&'a Buddy>> {
+  if let Some(buddy) = buddies.get(query) {
+    return Ok(Some(buddy));
+  }
+
+  let buddy = resolve_buddy(buddies, query)?;
+  match Confirm::new(&format!("Forget buddy '{}' ({buddy})?", buddy.alias))
+    .with_default(false)
+    .prompt()
+  {
+    Ok(confirmed) => Ok(confirmed.then_some(buddy)),
+    Err(_) => Err(
+      SquadError::NotFound {
+        query: query.to_string(),
+        suggestions: vec![format!("{} ({buddy})", buddy.alias)],
+      }
+      .into(),
+    ),
+  }
+}
+
+/// Resolves all `aliases`. Unresolvable aliases are reported and skipped
+/// unless `strict` is set, in which case only exact aliases and emails are
+/// resolved and the first other one is returned as error.
+fn resolve_buddies<'a>(
+  buddies: &'a Buddies,
+  aliases: &[String],
+  strict: bool,
+) -> Result<Vec<&'a Buddy>> {
+  let mut resolved = Vec::new();
+
+  for alias in aliases {
+    if strict {
+      resolved.push(exact_buddy(buddies, alias)?);
+      continue;
+    }
+
+    match resolve_buddy(buddies, alias) {
+      Ok(buddy) => resolved.push(buddy),
+      Err(e) => eprintln!("{e}"),
+    }
+  }
+
+  Ok(resolved)
+}
+
+fn validation_result<T>(result: Result<T>) -> Validation {
+  match result {
+    Ok(_) => Validation::Valid,
+    Err(e) => Validation::Invalid(e.to_string().into()),
+  }
 }
@@ -0,0 +1,215 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+  buddy::Buddy,
+  git::{RepoContext, UnknownCoAuthor},
+  ledger::LedgerEntry,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+  /// Human readable output
+  #[default]
+  Human,
+  /// A JSON array of records
+  Json,
+  /// A YAML sequence of records
+  Yaml,
+  /// Tab separated values with a header row
+  Tsv,
+  /// Stable, space separated text format for scripts
+  Porcelain,
+}
+
+/// The output formats rendered the same way by every command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineFormat {
+  Json,
+  Yaml,
+  Tsv,
+  Porcelain,
+}
+
+impl OutputFormat {
+  /// The machine readable format, `None` for human readable output which
+  /// every command renders itself
+  pub fn machine(self) -> Option<MachineFormat> {
+    match self {
+      OutputFormat::Human => None,
+      OutputFormat::Json => Some(MachineFormat::Json),
+      OutputFormat::Yaml => Some(MachineFormat::Yaml),
+      OutputFormat::Tsv => Some(MachineFormat::Tsv),
+      OutputFormat::Porcelain => Some(MachineFormat::Porcelain),
+    }
+  }
+}
+
+/// The machine readable representation of a buddy
+#[derive(Debug, Serialize)]
+pub struct BuddyRecord<'a> {
+  /// `None` for co-authors that are not in the buddies file
+  pub alias: Option<&'a str>,
+  pub name: &'a str,
+  /// The email used for the current repository
+  pub email: String,
+  /// Whether the buddy is part of the current session
+  pub active: bool,
+  /// Whether the buddy is in the buddies file
+  pub known: bool,
+}
+
+impl<'a> BuddyRecord<'a> {
+  pub fn new(buddy: &'a Buddy, repo: &RepoContext, active: bool) -> Self {
+    BuddyRecord {
+      alias: Some(&buddy.alias),
+      name: &buddy.name,
+      email: buddy.email_for(repo),
+      active,
+      known: true,
+    }
+  }
+
+  /// The record of a co-author in the session that is not in the buddies
+  /// file. Malformed trailers keep their whole value as name.
+  pub fn unknown(co_author: &'a UnknownCoAuthor) -> Self {
+    BuddyRecord {
+      alias: None,
+      name: co_author.name().unwrap_or(co_author.contact()),
+      email: co_author.email().unwrap_or_default(),
+      active: true,
+      known: false,
+    }
+  }
+}
+
+/// Renders `records` in one of the machine readable formats
+pub fn render_buddies(
+  format: MachineFormat,
+  records: &[BuddyRecord],
+) -> Result<String> {
+  let mut out = String::new();
+
+  match format {
+    MachineFormat::Json => {
+      out = serde_json::to_string_pretty(records)
+        .context("Failed to serialize buddies")?;
+      out.push('\n');
+    }
+    MachineFormat::Yaml => {
+      out = serde_yaml::to_string(records)
+        .context("Failed to serialize buddies")?;
+    }
+    MachineFormat::Tsv => {
+      out.push_str("alias\tname\temail\tactive\tknown\n");
+      for record in records {
+        writeln!(
+          out,
+          "{}\t{}\t{}\t{}\t{}",
+          record.alias.unwrap_or_default(),
+          record.name,
+          record.email,
+          record.active,
+          record.known
+        )?;
+      }
+    }
+    MachineFormat::Porcelain => {
+      for record in records {
+        let status = match record {
+          BuddyRecord { known: false, .. } => 'U',
+          BuddyRecord { active: true, .. } => 'A',
+          BuddyRecord { active: false, .. } => 'I',
+        };
+        writeln!(
+          out,
+          "{status} {} {} {}",
+          record.alias.unwrap_or("-"),
+          record.email,
+          record.name
+        )?;
+      }
+    }
+  }
+
+  Ok(out)
+}
+
+/// Renders a list of plain messages, e.g. problems found in the buddies file,
+/// in one of the machine readable formats.
+pub fn render_messages(
+  format: MachineFormat,
+  messages: &[String],
+) -> Result<String> {
+  match format {
+    MachineFormat::Json => Ok(
+      serde_json::to_string_pretty(messages)
+        .context("Failed to serialize output")?
+        + "\n",
+    ),
+    MachineFormat::Yaml => {
+      serde_yaml::to_string(messages).context("Failed to serialize output")
+    }
+    MachineFormat::Tsv | MachineFormat::Porcelain => {
+      let mut out = String::new();
+      for message in messages {
+        writeln!(out, "{message}")?;
+      }
+      Ok(out)
+    }
+  }
+}
+
+/// Renders the commits of the ledger in one of the machine readable formats.
+pub fn render_ledger(
+  format: MachineFormat,
+  entries: &[LedgerEntry],
+) -> Result<String> {
+  let mut out = String::new();
+
+  match format {
+    MachineFormat::Json => {
+      out = serde_json::to_string_pretty(entries)
+        .context("Failed to serialize ledger")?;
+      out.push('\n');
+    }
+    MachineFormat::Yaml => {
+      out =
+        serde_yaml::to_string(entries).context("Failed to serialize ledger")?;
+    }
+    MachineFormat::Tsv => {
+      out.push_str("sha\tbranch\ttimestamp\tbuddies\n");
+      for entry in entries {
+        writeln!(
+          out,
+          "{}\t{}\t{}\t{}",
+          entry.sha,
+          entry.branch.as_deref().unwrap_or_default(),
+          entry.timestamp,
+          entry.buddies.join(",")
+        )?;
+      }
+    }
+    MachineFormat::Porcelain => {
+      for entry in entries {
+        let buddies = if entry.buddies.is_empty() {
+          "-".to_string()
+        } else {
+          entry.buddies.join(",")
+        };
+        writeln!(
+          out,
+          "{} {} {} {buddies}",
+          entry.sha,
+          entry.timestamp,
+          entry.branch.as_deref().unwrap_or("-"),
+        )?;
+      }
+    }
+  }
+
+  Ok(out)
+}
@@ -0,0 +1,58 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::git::Repo;
+
+const POLICY_FILE: &str = ".git-squad-policy.toml";
+
+/// The co-author rules of a team, read from `.git-squad-policy.toml` in the
+/// root of the repository
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+  /// The domains co-author emails may use, any domain if empty
+  pub allowed_domains: Vec<String>,
+  /// The minimum number of co-authors of every commit
+  pub min_co_authors: Option<usize>,
+  /// The maximum number of co-authors of every commit
+  pub max_co_authors: Option<usize>,
+  /// Accept co-authors that are not in the buddies file
+  pub allow_unknown: bool,
+}
+
+impl Policy {
+  pub fn path(repo: &Repo) -> Result<PathBuf> {
+    Ok(repo.toplevel()?.join(POLICY_FILE))
+  }
+
+  /// Reads the policy of `repo`, `None` if it doesn't have one.
+  pub fn load(repo: &Repo) -> Result<Option<Self>> {
+    let path = Self::path(repo)?;
+
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&path)
+      .with_context(|| format!("Failed to read '{}'", path.display()))?;
+    toml::from_str(&contents)
+      .with_context(|| format!("Failed to parse '{}'", path.display()))
+      .map(Some)
+  }
+
+  /// Checks if the domain of `email` is allowed.
+  pub fn allows_domain(&self, email: &str) -> bool {
+    if self.allowed_domains.is_empty() {
+      return true;
+    }
+
+    email.rsplit_once('@').is_some_and(|(_, domain)| {
+      self
+        .allowed_domains
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+    })
+  }
+}
@@ -0,0 +1,251 @@
+use std::{
+  env, fs,
+  path::{Path, PathBuf},
+  time::UNIX_EPOCH,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  buddy::Buddies,
+  config::{ConfigService, FileConfig},
+  git::{Repo, RepoContext},
+};
+
+const CACHE_FILE: &str = "git-squad/prompt-cache.json";
+
+/// The cached output of the prompt command for the current repository
+#[derive(Debug, Serialize, Deserialize)]
+struct PromptCache {
+  format: String,
+  noreply: bool,
+  /// The buddies file the output was rendered from
+  buddies_file: PathBuf,
+  /// The `commit.template` setting and the template it resolved to
+  template: Option<(String, PathBuf)>,
+  /// Modification times of all files the output depends on
+  stamps: Vec<FileStamp>,
+  output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+struct FileStamp {
+  path: PathBuf,
+  /// Nanoseconds since the epoch or `None` if the file doesn't exist
+  mtime: Option<u128>,
+}
+
+impl FileStamp {
+  fn new(path: PathBuf) -> Self {
+    let mtime = fs::metadata(&path)
+      .and_then(|metadata| metadata.modified())
+      .ok()
+      .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+      .map(|duration| duration.as_nanos());
+
+    FileStamp { path, mtime }
+  }
+
+  fn is_fresh(&self) -> bool {
+    *self == FileStamp::new(self.path.clone())
+  }
+}
+
+/// Renders the prompt segment for the current session. Serves the output
+/// from a cache in the git directory as long as the same buddies file and
+/// commit template are used and none of them and the git config files
+/// changed, so that the common case doesn't spawn any processes.
+pub fn render(
+  conf: &FileConfig,
+  repo: &Repo,
+  format: &str,
+  noreply: bool,
+) -> Result<String> {
+  let cache_file =
+    find_git_dir(repo.dir()).map(|git_dir| git_dir.join(CACHE_FILE));
+
+  let buddies_file = absolute(conf.get_buddies_file()?);
+
+  if let Some(cache) = cache_file.as_deref().and_then(read_cache)
+    && cache.format == format
+    && cache.noreply == noreply
+    && cache.buddies_file == buddies_file
+    // The setting is current while the config files are unchanged
+    && cache.template.as_ref().is_none_or(|(setting, template)| {
+      absolute(repo.resolve_template_path(setting)) == *template
+    })
+    && cache.stamps.iter().all(FileStamp::is_fresh)
+  {
+    return Ok(cache.output);
+  }
+
+  // Without a template there is no session to show
+  let setting = repo.get_config("commit.template").ok().flatten();
+  let template = setting
+    .as_deref()
+    .map(|setting| absolute(repo.resolve_template_path(setting)));
+  let output = match template {
+    Some(_) => {
+      let buddies = conf.load_buddies()?;
+      let active_buddies = repo.get_active_buddies(&buddies)?;
+      let mut context = repo.context()?;
+      context.noreply |= noreply;
+      format_prompt(format, &active_buddies, &context)
+    }
+    None => String::new(),
+  };
+
+  if let Some(cache_file) = cache_file {
+    let git_dir = cache_file.parent().and_then(Path::parent);
+    let mut files = vec![buddies_file.clone()];
+    files.extend(template.clone());
+    files.extend(git_config_files(git_dir));
+
+    let cache = PromptCache {
+      format: format.to_string(),
+      noreply,
+      buddies_file,
+      template: setting.zip(template),
+      stamps: files.into_iter().map(FileStamp::new).collect(),
+      output: output.clone(),
+    };
+
+    // The cache is an optimisation only, failing to write it is fine
+    if let Ok(contents) = serde_json::to_string(&cache)
+      && let Some(cache_dir) = cache_file.parent()
+      && fs::create_dir_all(cache_dir).is_ok()
+    {
+      let _ = fs::write(&cache_file, contents);
+    }
+  }
+
+  Ok(output)
+}
+
+/// Replaces the placeholders in `format` with the details of the active
+/// buddies. Returns an empty string if no buddies are active.
+pub fn format_prompt(
+  format: &str,
+  active_buddies: &Buddies,
+  repo: &RepoContext,
+) -> String {
+  if active_buddies.buddies.is_empty() {
+    return String::new();
+  }
+
+  let buddies = &active_buddies.buddies;
+  let aliases: Vec<_> = buddies.iter().map(|b| b.alias.clone()).collect();
+  let names: Vec<_> = buddies.iter().map(|b| b.name.clone()).collect();
+  let emails: Vec<_> = buddies.iter().map(|b| b.email_for(repo)).collect();
+
+  format
+    .replace("{aliases}", &aliases.join(", "))
+    .replace("{names}", &names.join(", "))
+    .replace("{emails}", &emails.join(", "))
+    .replace("{count}", &buddies.len().to_string())
+}
+
+/// `path` relative to the current directory, so that cached paths stay valid
+/// when the prompt is rendered from another directory
+fn absolute(path: PathBuf) -> PathBuf {
+  std::path::absolute(&path).unwrap_or(path)
+}
+
+fn read_cache(path: &Path) -> Option<PromptCache> {
+  let contents = fs::read_to_string(path).ok()?;
+  serde_json::from_str(&contents).ok()
+}
+
+/// Finds the git directory by walking up from `start`, or the current
+/// directory, without spawning git.
+fn find_git_dir(start: Option<&Path>) -> Option<PathBuf> {
+  if let Some(git_dir) = env::var_os("GIT_DIR") {
+    return Some(PathBuf::from(git_dir));
+  }
+
+  let cwd = env::current_dir().ok()?;
+  let start = start.map_or_else(|| cwd.clone(), |start| cwd.join(start));
+  for dir in start.ancestors() {
+    let dot_git = dir.join(".git");
+
+    if dot_git.is_dir() {
+      return Some(dot_git);
+    }
+
+    // Worktrees and submodules use a file pointing to the git directory
+    if dot_git.is_file() {
+      let contents = fs::read_to_string(&dot_git).ok()?;
+      let git_dir = contents.strip_prefix("gitdir:")?.trim();
+      return Some(dir.join(git_dir));
+    }
+  }
+
+  None
+}
+
+/// All config files git reads that may affect the output, i.e. the commit
+/// template location, remotes and the noreply setting.
+fn git_config_files(git_dir: Option<&Path>) -> Vec<PathBuf> {
+  let mut files = vec![PathBuf::from("/etc/gitconfig")];
+
+  if let Some(home) = dirs::home_dir() {
+    files.push(home.join(".gitconfig"));
+  }
+
+  let xdg_config = env::var_os("XDG_CONFIG_HOME")
+    .map(PathBuf::from)
+    .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
+  if let Some(xdg_config) = xdg_config {
+    files.push(xdg_config.join("git").join("config"));
+  }
+
+  if let Some(git_dir) = git_dir {
+    files.push(git_dir.join("config"));
+    files.push(git_dir.join("config.worktree"));
+
+    // Linked worktrees share the config of the main repository
+    if let Ok(common_dir) = fs::read_to_string(git_dir.join("commondir")) {
+      files.push(git_dir.join(common_dir.trim()).join("config"));
+    }
+  }
+
+  files
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::buddy::Buddy;
+
+  #[test]
+  fn test_format_prompt() {
+    let buddies = Buddies::new(vec![
+      Buddy {
+        alias: "pp".to_string(),
+        name: "Peter Pan".to_string(),
+        email: "peter.pan@example.com".to_string(),
+        emails: vec![],
+        github: None,
+      },
+      Buddy {
+        alias: "wd".to_string(),
+        name: "Wendy Darling".to_string(),
+        email: "wendy.darling@example.com".to_string(),
+        emails: vec![],
+        github: None,
+      },
+    ]);
+    let repo = RepoContext::default();
+
+    assert_eq!(
+      format_prompt("👥 {aliases} ({count})", &buddies, &repo),
+      "👥 pp, wd (2)"
+    );
+    assert_eq!(
+      format_prompt("{names}", &buddies, &repo),
+      "Peter Pan, Wendy Darling"
+    );
+    assert_eq!(format_prompt("{aliases}", &Buddies::default(), &repo), "");
+  }
+}
@@ -0,0 +1,180 @@
+use crate::buddy::{Buddies, Buddy};
+
+// Minimum similarity for a fuzzy match to resolve a buddy
+const MATCH_THRESHOLD: f64 = 0.65;
+// Minimum similarity for a buddy to be suggested when nothing matched
+const SUGGESTION_THRESHOLD: f64 = 0.3;
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, PartialEq)]
+pub enum Resolution<'a> {
+  /// Exactly one buddy matches
+  Found(&'a Buddy),
+  /// Several buddies match equally well
+  Ambiguous(Vec<&'a Buddy>),
+  /// No buddy matches, contains similar buddies ranked by similarity
+  NotFound(Vec<&'a Buddy>),
+}
+
+/// Checks if the alias, the name or one of its words or an email of `buddy`
+/// start with `prefix`, ignoring case.
+pub fn matches_prefix(buddy: &Buddy, prefix: &str) -> bool {
+  let prefix = prefix.to_lowercase();
+
+  [buddy.alias.as_str(), buddy.name.as_str()]
+    .into_iter()
+    .chain(buddy.name.split_whitespace())
+    .chain(buddy.all_emails())
+    .any(|field| field.to_lowercase().starts_with(&prefix))
+}
+
+/// Resolves `query` to a buddy. Tries the exact alias and email first, then
+/// unique prefixes of alias, name and email and finally fuzzy matches.
+pub fn resolve<'a>(buddies: &'a Buddies, query: &str) -> Resolution<'a> {
+  if let Some(buddy) = buddies
+    .get(query)
+    .or_else(|| buddies.get_buddy_by_email(query))
+  {
+    return Resolution::Found(buddy);
+  }
+
+  let prefix_matches: Vec<_> = buddies
+    .buddies
+    .iter()
+    .filter(|buddy| matches_prefix(buddy, query))
+    .collect();
+
+  if !prefix_matches.is_empty() {
+    return from_matches(prefix_matches);
+  }
+
+  let mut ranked: Vec<_> = buddies
+    .buddies
+    .iter()
+    .map(|buddy| (similarity(buddy, query), buddy))
+    .filter(|(score, _)| *score >= SUGGESTION_THRESHOLD)
+    .collect();
+  ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+  let fuzzy_matches: Vec<_> = ranked
+    .iter()
+    .filter(|(score, _)| *score >= MATCH_THRESHOLD)
+    .map(|(_, buddy)| *buddy)
+    .collect();
+
+  if !fuzzy_matches.is_empty() {
+    return from_matches(fuzzy_matches);
+  }
+
+  Resolution::NotFound(
+    ranked
+      .into_iter()
+      .take(MAX_SUGGESTIONS)
+      .map(|(_, buddy)| buddy)
+      .collect(),
+  )
+}
+
+fn from_matches(mut matches: Vec<&Buddy>) -> Resolution<'_> {
+  if matches.len() == 1 {
+    Resolution::Found(matches.remove(0))
+  } else {
+    Resolution::Ambiguous(matches)
+  }
+}
+
+/// The best similarity between `query` and the alias, the name or one of its
+/// words and the local parts of the emails of `buddy`.
+fn similarity(buddy: &Buddy, query: &str) -> f64 {
+  let query = query.to_lowercase();
+
+  [buddy.alias.as_str(), buddy.name.as_str()]
+    .into_iter()
+    .chain(buddy.name.split_whitespace())
+    .chain(
+      buddy
+        .all_emails()
+        .filter_map(|email| email.split_once('@').map(|(local, _)| local)),
+    )
+    .map(|field| {
+      strsim::normalized_damerau_levenshtein(&field.to_lowercase(), &query)
+    })
+    .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn buddies() -> Buddies {
+    let buddy = |alias: &str, name: &str, email: &str| Buddy {
+      alias: alias.to_string(),
+      name: name.to_string(),
+      email: email.to_string(),
+      emails: vec![],
+      github: None,
+    };
+
+    Buddies::new(vec![
+      buddy("pp", "Peter Pan", "peter.pan@example.com"),
+      buddy("wd", "Wendy Darling", "wendy.darling@example.com"),
+      buddy("jd", "John Darling", "john.darling@example.com"),
+    ])
+  }
+
+  fn aliases(buddies: &[&Buddy]) -> Vec<String> {
+    buddies.iter().map(|buddy| buddy.alias.clone()).collect()
+  }
+
+  #[test]
+  fn test_resolve_exact() {
+    let buddies = buddies();
+    assert_eq!(
+      resolve(&buddies, "wd"),
+      Resolution::Found(&buddies.buddies[1])
+    );
+    assert_eq!(
+      resolve(&buddies, "john.darling@example.com"),
+      Resolution::Found(&buddies.buddies[2])
+    );
+  }
+
+  #[test]
+  fn test_resolve_prefix() {
+    let buddies = buddies();
+    assert_eq!(
+      resolve(&buddies, "wen"),
+      Resolution::Found(&buddies.buddies[1])
+    );
+
+    let Resolution::Ambiguous(matches) = resolve(&buddies, "darl") else {
+      panic!("expected an ambiguous resolution");
+    };
+    assert_eq!(aliases(&matches), vec!["wd", "jd"]);
+  }
+
+  #[test]
+  fn test_resolve_fuzzy() {
+    let buddies = buddies();
+    assert_eq!(
+      resolve(&buddies, "ppp"),
+      Resolution::Found(&buddies.buddies[0])
+    );
+    assert_eq!(
+      resolve(&buddies, "wnedy"),
+      Resolution::Found(&buddies.buddies[1])
+    );
+  }
+
+  #[test]
+  fn test_resolve_not_found() {
+    let buddies = buddies();
+
+    let Resolution::NotFound(suggestions) = resolve(&buddies, "dj") else {
+      panic!("expected no match");
+    };
+    assert_eq!(aliases(&suggestions), vec!["jd"]);
+
+    assert_eq!(resolve(&buddies, "hook"), Resolution::NotFound(vec![]));
+  }
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  buddy::{Buddies, Buddy},
+  git::Repo,
+  write,
+};
+
+const SESSIONS_FILE: &str = "git-squad/sessions.toml";
+
+/// Sessions bound to branches, stored in the git directory shared by all
+/// worktrees of a repository
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Sessions {
+  /// The session of all branches without a binding, saved when switching to
+  /// a bound branch
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub unbound: Option<Vec<String>>,
+  /// The aliases of the session bound to each branch
+  #[serde(default)]
+  pub branches: BTreeMap<String, Vec<String>>,
+}
+
+impl Sessions {
+  pub fn path(repo: &Repo) -> Result<PathBuf> {
+    Ok(repo.git_common_dir()?.join(SESSIONS_FILE))
+  }
+
+  pub fn load(repo: &Repo) -> Result<Self> {
+    let path = Self::path(repo)?;
+
+    if !path.exists() {
+      return Ok(Sessions::default());
+    }
+
+    let contents = fs::read_to_string(&path)
+      .with_context(|| format!("Failed to read '{}'", path.display()))?;
+    toml::from_str(&contents)
+      .with_context(|| format!("Failed to parse '{}'", path.display()))
+  }
+
+  pub fn save(&self, repo: &Repo, dry_run: bool) -> Result<()> {
+    let path = Self::path(repo)?;
+    let contents =
+      toml::to_string(self).context("Failed to serialize sessions")?;
+
+    if !dry_run && let Some(dir) = path.parent() {
+      fs::create_dir_all(dir).with_context(|| {
+        format!("Failed to create directory '{}'", dir.display())
+      })?;
+    }
+
+    write::write_file(&path, &contents, dry_run)
+  }
+
+  pub fn bind(&mut self, branch: &str, session: &Buddies) {
+    self.branches.insert(branch.to_string(), aliases(session));
+  }
+
+  pub fn get(&self, branch: &str) -> Option<&Vec<String>> {
+    self.branches.get(branch)
+  }
+
+  /// Checks if `session` is exactly the session bound to `branch`
+  pub fn is_bound(&self, branch: &str, session: &Buddies) -> bool {
+    self.get(branch).is_some_and(|bound| {
+      let mut bound = bound.clone();
+      let mut active = aliases(session);
+      bound.sort();
+      active.sort();
+      bound == active
+    })
+  }
+
+  /// Switches from branch `from` to branch `to`, returning the session to
+  /// activate or `None` to keep `current`. The session of unbound branches is
+  /// saved when leaving them.
+  pub fn switch(
+    &mut self,
+    from: Option<&str>,
+    to: Option<&str>,
+    current: &Buddies,
+  ) -> Option<Vec<String>> {
+    let from_bound =
+      from.is_some_and(|branch| self.branches.contains_key(branch));
+    if !from_bound {
+      self.unbound = Some(aliases(current));
+    }
+
+    match to.and_then(|branch| self.get(branch)) {
+      Some(bound) => Some(bound.clone()),
+      None if from_bound => Some(self.unbound.clone().unwrap_or_default()),
+      None => None,
+    }
+  }
+}
+
+/// Looks up the buddies for `aliases`, skipping buddies that were forgotten.
+pub fn buddies_for(buddies: &Buddies, aliases: &[String]) -> Buddies {
+  Buddies::new(
+    aliases
+      .iter()
+      .filter_map(|alias| buddies.get(alias))
+      .cloned()
+      .collect::<Vec<Buddy>>(),
+  )
+}
+
+fn aliases(session: &Buddies) -> Vec<String> {
+  session.buddies.iter().map(|b| b.alias.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn session(aliases: &[&str]) -> Buddies {
+    Buddies::new(
+      aliases
+        .iter()
+        .map(|alias| Buddy {
+          alias: (*alias).to_string(),
+          name: alias.to_uppercase(),
+          email: format!("{alias}@example.com"),
+          emails: vec![],
+          github: None,
+        })
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn test_switch() {
+    let mut sessions = Sessions::default();
+    sessions.bind("feature", &session(&["wd"]));
+
+    // Leaving an unbound branch saves its session
+    assert_eq!(
+      sessions.switch(Some("main"), Some("feature"), &session(&["pp"])),
+      Some(vec!["wd".to_string()])
+    );
+    assert_eq!(sessions.unbound, Some(vec!["pp".to_string()]));
+
+    // Returning restores it
+    assert_eq!(
+      sessions.switch(Some("feature"), Some("main"), &session(&["wd"])),
+      Some(vec!["pp".to_string()])
+    );
+
+    // Switching between unbound branches keeps the session
+    assert_eq!(
+      sessions.switch(Some("main"), Some("fix"), &session(&["pp"])),
+      None
+    );
+  }
+
+  #[test]
+  fn test_is_bound() {
+    let mut sessions = Sessions::default();
+    sessions.bind("feature", &session(&["wd", "pp"]));
+
+    assert!(sessions.is_bound("feature", &session(&["pp", "wd"])));
+    assert!(!sessions.is_bound("feature", &session(&["pp"])));
+    assert!(!sessions.is_bound("main", &session(&["pp", "wd"])));
+  }
+}
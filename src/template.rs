@@ -0,0 +1,733 @@
+use std::{fmt, path::Path};
+
+use anyhow::Result;
+
+use crate::{error::SquadError, trailer::TrailerConfig};
+
+// Markers for the git-squad section in the commit template, following the
+// comment string of git
+const BEGIN_MARKER: &str = "BEGIN GIT-SQUAD";
+const END_MARKER: &str = "END GIT-SQUAD";
+// Marks a buddy in the git-squad section to be removed after the next commit
+const ONCE_MARKER: &str = "ONCE: ";
+
+/// The line of the commit template the git-squad section is rendered at,
+/// unless `squad.placeholder` configures another one
+pub const DEFAULT_PLACEHOLDER: &str = "{{git-squad}}";
+
+/// How the git-squad section is written to the commit template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionFormat {
+  /// Between marker comments starting with the comment string, which git
+  /// strips from the message
+  Markers(String),
+  /// As the co-author trailers at the end of the template, for commits that
+  /// keep comments in the message
+  Trailers,
+}
+
+impl SectionFormat {
+  /// Fails for formats that can't mark one-shot buddies, which needs
+  /// comments.
+  pub fn check_one_shot(&self) -> Result<()> {
+    if *self == SectionFormat::Trailers {
+      anyhow::bail!(
+        "One-shot buddies can't be marked in a commit template git doesn't \
+         strip comments from, check core.commentChar and commit.cleanup"
+      );
+    }
+
+    Ok(())
+  }
+}
+
+impl Default for SectionFormat {
+  fn default() -> Self {
+    SectionFormat::Markers("#".to_string())
+  }
+}
+
+impl fmt::Display for SectionFormat {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SectionFormat::Markers(comment) => {
+        write!(f, "marker comments starting with '{comment}'")
+      }
+      SectionFormat::Trailers => f.write_str("trailers without markers"),
+    }
+  }
+}
+
+/// A problem with the git-squad section of a commit template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Problem {
+  /// The line of the template the problem was found in, starting at 1
+  pub line: usize,
+  pub kind: ProblemKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemKind {
+  /// A begin marker without end marker
+  Unclosed,
+  /// An end marker without begin marker
+  UnexpectedEnd,
+  /// A begin marker inside of a section
+  Nested,
+  /// More than one section
+  Duplicate,
+  /// A marker with leading whitespace, which git doesn't strip as a comment
+  Indented,
+}
+
+impl ProblemKind {
+  /// Problems that could lose lines of the template if it was rewritten
+  /// without repairing it first.
+  pub fn is_destructive(self) -> bool {
+    matches!(
+      self,
+      ProblemKind::Unclosed | ProblemKind::UnexpectedEnd | ProblemKind::Nested
+    )
+  }
+}
+
+impl fmt::Display for ProblemKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      ProblemKind::Unclosed => "git-squad section is never closed",
+      ProblemKind::UnexpectedEnd => "end marker without begin marker",
+      ProblemKind::Nested => "nested git-squad section",
+      ProblemKind::Duplicate => "duplicate git-squad section",
+      ProblemKind::Indented => "indented git-squad marker",
+    })
+  }
+}
+
+/// A commit template split into the text of the user and the contents of the
+/// git-squad section
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Template {
+  /// The lines outside of git-squad sections
+  text: Vec<String>,
+  /// The co-author trailers of all git-squad sections
+  co_authors: Vec<String>,
+  /// The aliases of the buddies marked to be removed after the next commit
+  one_shot: Vec<String>,
+  /// The format of the first git-squad section, if there is one
+  format: Option<SectionFormat>,
+  /// The line of the text the section is rendered at instead of the end:
+  /// the placeholder, or the first section if there is text below it
+  position: Option<usize>,
+  /// The placeholder line and the line of the text it was at, kept in place
+  /// by formats that can't be placed
+  placeholder: Option<(usize, String)>,
+  /// `\r\n` for templates with Windows line endings
+  line_ending: &'static str,
+  pub problems: Vec<Problem>,
+}
+
+/// A marker line of the git-squad section
+enum Marker<'a> {
+  Begin,
+  End,
+  Once(&'a str),
+}
+
+impl Template {
+  /// Parses `contents`, recovering from broken sections: a section without
+  /// end marker ends at the first line that can't be part of it. Markers are
+  /// recognized with any comment string. Without markers, the co-author
+  /// trailers at the end of the template are the section. Co-authors are
+  /// recognized with the trailer settings of `config`, and folded trailers
+  /// are joined into one line. A line consisting of the `placeholder`,
+  /// commented out or not, marks where the section goes.
+  pub fn parse(
+    contents: &str,
+    config: &TrailerConfig,
+    placeholder: &str,
+  ) -> Self {
+    let mut template = Template {
+      line_ending: if contents.contains("\r\n") {
+        "\r\n"
+      } else {
+        "\n"
+      },
+      ..Template::default()
+    };
+
+    let mut open_section = None;
+    let mut sections = 0;
+    let mut after_co_author = false;
+    let mut first_section_at = None;
+
+    for (index, line) in contents.lines().enumerate() {
+      let line_number = index + 1;
+      let trimmed = line.trim();
+      let mut problem = |kind| {
+        template.problems.push(Problem {
+          line: line_number,
+          kind,
+        });
+      };
+
+      match parse_marker(trimmed) {
+        Some((comment, Marker::Begin)) => {
+          if line.trim_end() != trimmed {
+            problem(ProblemKind::Indented);
+          }
+          if open_section.is_some() {
+            problem(ProblemKind::Nested);
+          } else {
+            sections += 1;
+            if sections > 1 {
+              problem(ProblemKind::Duplicate);
+            }
+            template
+              .format
+              .get_or_insert_with(|| SectionFormat::Markers(comment.into()));
+            first_section_at.get_or_insert(template.text.len());
+            open_section = Some(line_number);
+          }
+        }
+        Some((_, Marker::End)) => {
+          if line.trim_end() != trimmed {
+            problem(ProblemKind::Indented);
+          }
+          if open_section.take().is_none() {
+            problem(ProblemKind::UnexpectedEnd);
+          }
+        }
+        Some((_, Marker::Once(alias))) if open_section.is_some() => {
+          template.one_shot.push(alias.to_string());
+        }
+        _ if after_co_author && is_continuation(line) => {
+          let co_author = template.co_authors.last_mut().unwrap();
+          co_author.push(' ');
+          co_author.push_str(trimmed);
+          continue;
+        }
+        _ if open_section.is_none() && is_placeholder(trimmed, placeholder) => {
+          template
+            .placeholder
+            .get_or_insert((template.text.len(), line.to_string()));
+        }
+        _ if open_section.is_some()
+          && (trimmed.is_empty() || is_co_author(config, trimmed)) =>
+        {
+          if !trimmed.is_empty() {
+            template.co_authors.push(trimmed.to_string());
+            after_co_author = true;
+            continue;
+          }
+        }
+        _ => {
+          if let Some(begin) = open_section.take() {
+            template.problems.push(Problem {
+              line: begin,
+              kind: ProblemKind::Unclosed,
+            });
+          }
+          template.text.push(line.to_string());
+        }
+      }
+
+      after_co_author = false;
+    }
+
+    if let Some(begin) = open_section {
+      template.problems.push(Problem {
+        line: begin,
+        kind: ProblemKind::Unclosed,
+      });
+    }
+    template.problems.sort_by_key(|problem| problem.line);
+
+    let placeholder_at = template.placeholder.as_ref().map(|(at, _)| *at);
+    template.place_section(placeholder_at, first_section_at);
+
+    if sections == 0 {
+      template.take_trailers(config);
+    }
+
+    template
+  }
+
+  /// Sets where the section is rendered from the lines of the text the
+  /// placeholder and the first section were at.
+  fn place_section(
+    &mut self,
+    placeholder_at: Option<usize>,
+    first_section_at: Option<usize>,
+  ) {
+    // A section moved up by hand or from the placeholder stays in place,
+    // unless it is too broken to tell where it ended
+    let first_section_at = first_section_at.filter(|&at| {
+      self.text[at..].iter().any(|line| !line.trim().is_empty())
+        && !self.problems.iter().any(|p| p.kind.is_destructive())
+    });
+
+    self.position = match (placeholder_at, first_section_at) {
+      (Some(a), Some(b)) => Some(a.min(b)),
+      (a, b) => a.or(b),
+    };
+  }
+
+  /// Moves the co-author trailers at the end of the text into the section.
+  fn take_trailers(&mut self, config: &TrailerConfig) {
+    let end = self
+      .text
+      .iter()
+      .rposition(|line| !line.trim().is_empty())
+      .map_or(0, |index| index + 1);
+    let start = self.text[..end]
+      .iter()
+      .rposition(|line| {
+        !(is_co_author(config, line.trim()) || is_continuation(line))
+      })
+      .map_or(0, |index| index + 1);
+
+    // Trailers are separated from the rest of the message by a blank line
+    if start == end
+      || is_continuation(&self.text[start])
+      || (start > 0 && !self.text[start - 1].trim().is_empty())
+    {
+      return;
+    }
+
+    self.co_authors.clear();
+    for line in self.text.drain(start..) {
+      let trimmed = line.trim();
+      if trimmed.is_empty() {
+        continue;
+      }
+      if is_continuation(&line)
+        && let Some(co_author) = self.co_authors.last_mut()
+      {
+        co_author.push(' ');
+        co_author.push_str(trimmed);
+      } else {
+        self.co_authors.push(trimmed.to_string());
+      }
+    }
+    self.format = Some(SectionFormat::Trailers);
+  }
+
+  /// Parses `contents` of the template at `path`, failing on problems that
+  /// would lose lines if the template was rewritten.
+  pub fn parse_checked(
+    path: &Path,
+    contents: &str,
+    config: &TrailerConfig,
+    placeholder: &str,
+  ) -> Result<Self> {
+    let template = Self::parse(contents, config, placeholder);
+
+    if let Some(problem) =
+      template.problems.iter().find(|p| p.kind.is_destructive())
+    {
+      return Err(
+        SquadError::TemplateParse {
+          path: path.to_path_buf(),
+          line: problem.line,
+          message: format!(
+            "{}, run `git squad doctor --fix` to repair it",
+            problem.kind
+          ),
+        }
+        .into(),
+      );
+    }
+
+    Ok(template)
+  }
+
+  /// The co-author trailers of the git-squad section
+  pub fn co_authors(&self) -> impl Iterator<Item = &str> {
+    self.co_authors.iter().map(String::as_str)
+  }
+
+  /// The aliases of the buddies marked to be removed after the next commit
+  pub fn one_shot_aliases(&self) -> &[String] {
+    &self.one_shot
+  }
+
+  /// The format the git-squad section was written in, if there is one
+  pub fn format(&self) -> Option<&SectionFormat> {
+    self.format.as_ref()
+  }
+
+  /// The template with its git-squad section replaced by `co_authors` and
+  /// the `one_shot` markers in `format`, placed where the section or the
+  /// placeholder was or after the text of the user. Without co-authors no
+  /// section is written, except for empty markers that keep the place of a
+  /// placed section. Trailers are always placed at the end, where git looks
+  /// for them, and leave the placeholder where it was. They can't mark
+  /// one-shot buddies, callers have to check that there are none.
+  pub fn render(
+    &self,
+    format: &SectionFormat,
+    co_authors: &[String],
+    one_shot: &[String],
+  ) -> String {
+    let line_ending = self.line_ending;
+
+    let mut lines = Vec::new();
+    match format {
+      SectionFormat::Markers(comment) => {
+        lines.push(format!("{comment} {BEGIN_MARKER}"));
+        lines.extend(co_authors.iter().cloned());
+        lines.extend(
+          one_shot
+            .iter()
+            .map(|alias| format!("{comment} {ONCE_MARKER}{alias}")),
+        );
+        lines.push(format!("{comment} {END_MARKER}"));
+
+        if let Some(position) = self.position {
+          let mut contents = self.text[..position]
+            .iter()
+            .chain(&lines)
+            .chain(&self.text[position..])
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(line_ending)
+            .trim_end()
+            .to_string();
+          contents.push_str(line_ending);
+          return contents;
+        }
+      }
+      SectionFormat::Trailers => lines.extend(co_authors.iter().cloned()),
+    }
+
+    let mut text = self.text.iter().map(String::as_str).collect::<Vec<_>>();
+    if let Some((at, line)) = &self.placeholder {
+      text.insert(*at, line);
+    }
+    let mut contents = text.join(line_ending).trim_end().to_string();
+
+    if co_authors.is_empty() {
+      return contents;
+    }
+
+    contents.push_str(line_ending);
+    for line in lines {
+      contents.push_str(line_ending);
+      contents.push_str(&line);
+    }
+    contents.push_str(line_ending);
+
+    contents
+  }
+
+  /// The template with all problems fixed, merging all sections into one in
+  /// `format`.
+  pub fn repair(&self, format: &SectionFormat) -> String {
+    let mut co_authors: Vec<String> = Vec::new();
+    for line in self.co_authors() {
+      if !co_authors.iter().any(|l| l == line) {
+        co_authors.push(line.to_string());
+      }
+    }
+
+    let one_shot = match format {
+      SectionFormat::Markers(_) => self.one_shot.clone(),
+      SectionFormat::Trailers => vec![],
+    };
+
+    self.render(format, &co_authors, &one_shot)
+  }
+}
+
+/// Parses a marker of the git-squad section, returning its comment string
+fn parse_marker(line: &str) -> Option<(&str, Marker<'_>)> {
+  let (comment, rest) = line.split_once(' ')?;
+  if !is_comment_string(comment) {
+    return None;
+  }
+
+  let marker = match rest.trim() {
+    BEGIN_MARKER => Marker::Begin,
+    END_MARKER => Marker::End,
+    rest => Marker::Once(rest.strip_prefix(ONCE_MARKER)?.trim()),
+  };
+
+  Some((comment, marker))
+}
+
+/// Checks if `line` is the `placeholder`, optionally after a comment string
+fn is_placeholder(line: &str, placeholder: &str) -> bool {
+  !placeholder.is_empty()
+    && (line == placeholder
+      || line.split_once(' ').is_some_and(|(comment, rest)| {
+        is_comment_string(comment) && rest.trim() == placeholder
+      }))
+}
+
+/// Checks if `comment` can start a comment line, like `#` or `//`
+fn is_comment_string(comment: &str) -> bool {
+  !comment.is_empty() && comment.chars().all(|c| c.is_ascii_punctuation())
+}
+
+/// Checks if `line` is a co-author trailer
+fn is_co_author(config: &TrailerConfig, line: &str) -> bool {
+  config
+    .parse_line(line)
+    .is_some_and(|trailer| trailer.is_co_author())
+}
+
+/// Checks if `line` continues the trailer before it
+fn is_continuation(line: &str) -> bool {
+  line.starts_with([' ', '\t']) && !line.trim().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PP: &str = "Co-authored-by: Peter Pan <peter.pan@example.com>";
+  const WD: &str = "Co-authored-by: Wendy Darling <wendy.darling@example.com>";
+
+  fn problems(template: &Template) -> Vec<(usize, ProblemKind)> {
+    template.problems.iter().map(|p| (p.line, p.kind)).collect()
+  }
+
+  fn parse(contents: &str) -> Template {
+    Template::parse(contents, &TrailerConfig::default(), DEFAULT_PLACEHOLDER)
+  }
+
+  fn markers(comment: &str) -> SectionFormat {
+    SectionFormat::Markers(comment.to_string())
+  }
+
+  #[test]
+  fn test_parse() {
+    let template = parse(&format!(
+      "Subject\n\n# BEGIN GIT-SQUAD\n{PP}\n# ONCE: pp\n# END GIT-SQUAD\n"
+    ));
+
+    assert!(template.problems.is_empty());
+    assert_eq!(template.format(), Some(&markers("#")));
+    assert_eq!(template.one_shot_aliases(), ["pp"]);
+    assert_eq!(
+      template.render(&markers("#"), &[WD.to_string()], &["wd".to_string()]),
+      format!(
+        "Subject\n\n# BEGIN GIT-SQUAD\n{WD}\n# ONCE: wd\n# END GIT-SQUAD\n"
+      )
+    );
+    assert_eq!(template.render(&markers("#"), &[], &[]), "Subject");
+  }
+
+  #[test]
+  fn test_parse_crlf() {
+    let template = parse(&format!(
+      "Subject\r\n\r\n# BEGIN GIT-SQUAD\r\n{PP}\r\n# END GIT-SQUAD\r\n"
+    ));
+
+    assert!(template.problems.is_empty());
+    assert_eq!(template.co_authors().collect::<Vec<_>>(), vec![PP]);
+    assert_eq!(
+      template.render(&markers("#"), &[WD.to_string()], &[]),
+      format!(
+        "Subject\r\n\r\n# BEGIN GIT-SQUAD\r\n{WD}\r\n# END GIT-SQUAD\r\n"
+      )
+    );
+  }
+
+  #[test]
+  fn test_parse_comment_strings() {
+    let template = parse(&format!(
+      "Subject\n\n; BEGIN GIT-SQUAD\n{PP}\n; ONCE: pp\n; END GIT-SQUAD\n"
+    ));
+
+    assert!(template.problems.is_empty());
+    assert_eq!(template.format(), Some(&markers(";")));
+    assert_eq!(template.one_shot_aliases(), ["pp"]);
+    assert_eq!(
+      template.repair(&markers("//")),
+      format!(
+        "Subject\n\n// BEGIN GIT-SQUAD\n{PP}\n// ONCE: pp\n// END GIT-SQUAD\n"
+      )
+    );
+  }
+
+  #[test]
+  fn test_parse_trailers() {
+    let template = parse(&format!("Subject\n\n{PP}\n{WD}\n"));
+
+    assert_eq!(template.format(), Some(&SectionFormat::Trailers));
+    assert_eq!(template.co_authors().collect::<Vec<_>>(), vec![PP, WD]);
+    assert_eq!(
+      template.render(&SectionFormat::Trailers, &[WD.to_string()], &[]),
+      format!("Subject\n\n{WD}\n")
+    );
+    assert_eq!(
+      template.repair(&markers("#")),
+      format!("Subject\n\n# BEGIN GIT-SQUAD\n{PP}\n{WD}\n# END GIT-SQUAD\n")
+    );
+
+    // Co-authors in the body of the text aren't trailers
+    let template = parse(&format!("Subject\n{PP}\n"));
+    assert_eq!(template.format(), None);
+    assert_eq!(template.co_authors().count(), 0);
+  }
+
+  #[test]
+  fn test_repair_unclosed() {
+    let template =
+      parse(&format!("Subject\n\n# BEGIN GIT-SQUAD\n{PP}\nKeep me\n"));
+
+    assert_eq!(problems(&template), vec![(3, ProblemKind::Unclosed)]);
+    assert_eq!(
+      template.repair(&markers("#")),
+      format!(
+        "Subject\n\nKeep me\n\n# BEGIN GIT-SQUAD\n{PP}\n# END GIT-SQUAD\n"
+      )
+    );
+  }
+
+  #[test]
+  fn test_repair_duplicate_and_indented() {
+    let template = parse(&format!(
+      "Subject\n  # BEGIN GIT-SQUAD\n{PP}\n# END GIT-SQUAD\n# BEGIN \
+       GIT-SQUAD\n{PP}\n{WD}\n# END GIT-SQUAD\n# END GIT-SQUAD\n"
+    ));
+
+    assert_eq!(
+      problems(&template),
+      vec![
+        (2, ProblemKind::Indented),
+        (5, ProblemKind::Duplicate),
+        (9, ProblemKind::UnexpectedEnd)
+      ]
+    );
+    assert_eq!(
+      template.repair(&markers("#")),
+      format!("Subject\n\n# BEGIN GIT-SQUAD\n{PP}\n{WD}\n# END GIT-SQUAD\n")
+    );
+  }
+
+  #[test]
+  fn test_parse_checked() {
+    let path = Path::new("template.txt");
+
+    let config = TrailerConfig::default();
+
+    assert!(
+      Template::parse_checked(
+        path,
+        "  # BEGIN GIT-SQUAD\n# END GIT-SQUAD",
+        &config,
+        DEFAULT_PLACEHOLDER
+      )
+      .is_ok()
+    );
+    assert!(
+      Template::parse_checked(
+        path,
+        "# BEGIN GIT-SQUAD\n# BEGIN GIT-SQUAD",
+        &config,
+        DEFAULT_PLACEHOLDER
+      )
+      .is_err()
+    );
+  }
+
+  #[test]
+  fn test_parse_folded_co_authors() {
+    let template = parse(
+      "Subject\n\n# BEGIN GIT-SQUAD\nco-authored-by: Peter\n  Pan \
+       <peter.pan@example.com>\n# END GIT-SQUAD\n",
+    );
+    assert_eq!(
+      template.co_authors().collect::<Vec<_>>(),
+      vec!["co-authored-by: Peter Pan <peter.pan@example.com>"]
+    );
+
+    let template =
+      parse("Subject\n\nCo-authored-by: Wendy\n Darling <wendy@example.com>\n");
+    assert_eq!(
+      template.co_authors().collect::<Vec<_>>(),
+      vec!["Co-authored-by: Wendy Darling <wendy@example.com>"]
+    );
+    assert_eq!(template.format(), Some(&SectionFormat::Trailers));
+  }
+
+  #[test]
+  fn test_render_placeholder() {
+    let template = parse("[TICKET-]\n\n{{git-squad}}\n# Explain why\n");
+    let rendered = template.render(&markers("#"), &[PP.to_string()], &[]);
+    assert_eq!(
+      rendered,
+      format!(
+        "[TICKET-]\n\n# BEGIN GIT-SQUAD\n{PP}\n# END GIT-SQUAD\n# Explain \
+         why\n"
+      )
+    );
+
+    // The section stays in place, even without co-authors
+    let template = parse(&rendered);
+    let rendered = template.render(&markers("#"), &[], &[]);
+    assert_eq!(
+      rendered,
+      "[TICKET-]\n\n# BEGIN GIT-SQUAD\n# END GIT-SQUAD\n# Explain why\n"
+    );
+    assert_eq!(
+      parse(&rendered).render(&markers("#"), &[WD.to_string()], &[]),
+      format!(
+        "[TICKET-]\n\n# BEGIN GIT-SQUAD\n{WD}\n# END GIT-SQUAD\n# Explain \
+         why\n"
+      )
+    );
+
+    // Trailers are only trailers at the end of the message
+    assert_eq!(
+      template.render(&SectionFormat::Trailers, &[PP.to_string()], &[]),
+      format!("[TICKET-]\n\n# Explain why\n\n{PP}\n")
+    );
+  }
+
+  #[test]
+  fn test_render_commented_placeholder() {
+    let template = parse("[TICKET-]\n\n# {{git-squad}}\n# Explain why\n");
+    assert_eq!(
+      template.render(&markers("#"), &[PP.to_string()], &[]),
+      format!(
+        "[TICKET-]\n\n# BEGIN GIT-SQUAD\n{PP}\n# END GIT-SQUAD\n# Explain \
+         why\n"
+      )
+    );
+
+    // Trailers leave the placeholder for the next section with markers
+    let rendered =
+      template.render(&SectionFormat::Trailers, &[PP.to_string()], &[]);
+    assert_eq!(
+      rendered,
+      format!("[TICKET-]\n\n# {{{{git-squad}}}}\n# Explain why\n\n{PP}\n")
+    );
+    assert_eq!(
+      parse(&rendered).render(&markers("//"), &[WD.to_string()], &[]),
+      format!(
+        "[TICKET-]\n\n// BEGIN GIT-SQUAD\n{WD}\n// END GIT-SQUAD\n# \
+         Explain why\n"
+      )
+    );
+  }
+
+  #[test]
+  fn test_render_configured_placeholder() {
+    let template = Template::parse(
+      "Subject\n\n<co-authors>\n\nFooter\n{{git-squad}}\n",
+      &TrailerConfig::default(),
+      "<co-authors>",
+    );
+
+    assert_eq!(
+      template.render(&markers("#"), &[PP.to_string()], &[]),
+      format!(
+        "Subject\n\n# BEGIN GIT-SQUAD\n{PP}\n# END GIT-SQUAD\n\nFooter\n\
+         {{{{git-squad}}}}\n"
+      )
+    );
+  }
+}
@@ -0,0 +1,893 @@
+//! Commit message trailers, parsed and written the way `git
+//! interpret-trailers` does.
+
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::git::Repo;
+
+/// The token of co-author trailers
+pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+
+// Lines git adds itself, which mark a trailer block even when it has many
+// other lines
+const GIT_GENERATED_PREFIXES: [&str; 2] =
+  ["Signed-off-by: ", "(cherry picked from commit "];
+
+// The scissors line of `git commit --verbose` after the comment string
+const CUT_LINE: &str = "------------------------ >8 ------------------------\n";
+
+/// A trailer of a commit message, e.g. `Co-authored-by: Name <email>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+  pub token: String,
+  /// The value with continuation lines unfolded
+  pub value: String,
+}
+
+impl Trailer {
+  /// The co-author trailer for `name` and `email`
+  pub fn co_author(name: &str, email: &str) -> Self {
+    Trailer {
+      token: CO_AUTHORED_BY.to_string(),
+      value: format!("{name} <{email}>"),
+    }
+  }
+
+  /// Checks if the trailer lists a co-author, ignoring the case of the token
+  pub fn is_co_author(&self) -> bool {
+    self.token.eq_ignore_ascii_case(CO_AUTHORED_BY)
+  }
+
+  /// The name and email of a `Name <email>` value. The email is the part in
+  /// the last angle brackets, so names may contain `<` themselves. The name
+  /// may be empty.
+  pub fn contact(&self) -> Option<(&str, &str)> {
+    let (name, email) = self.value.strip_suffix('>')?.rsplit_once('<')?;
+    if email.is_empty() || email.contains('>') {
+      return None;
+    }
+
+    Some((name.trim_end(), email))
+  }
+}
+
+/// A trailer configured with `trailer.<name>.*`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrailerItem {
+  name: String,
+  /// The token written for trailers matching the item
+  key: Option<String>,
+}
+
+impl TrailerItem {
+  /// Checks if `token` abbreviates the name or key of the item, ignoring
+  /// case and anything after its last alphanumeric character.
+  fn matches(&self, token: &str) -> bool {
+    let len = token
+      .bytes()
+      .rposition(|c| c.is_ascii_alphanumeric())
+      .map_or(0, |i| i + 1);
+
+    token_matches(&token[..len], &self.name)
+      || self
+        .key
+        .as_ref()
+        .is_some_and(|key| token_matches(&token[..len], key))
+  }
+}
+
+/// The trailer settings of git that affect parsing and writing trailers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrailerConfig {
+  /// The characters separating token and value, the first one is written
+  separators: String,
+  /// The comment string, lines starting with it are ignored
+  comment: String,
+  items: Vec<TrailerItem>,
+}
+
+impl Default for TrailerConfig {
+  fn default() -> Self {
+    TrailerConfig {
+      separators: ":".to_string(),
+      comment: "#".to_string(),
+      items: Vec::new(),
+    }
+  }
+}
+
+/// A line of the trailer block with its continuation lines
+struct BlockItem {
+  /// The byte range of the lines in the message
+  range: Range<usize>,
+  trailer: Option<Trailer>,
+}
+
+impl TrailerConfig {
+  /// Reads `trailer.separators`, the `trailer.<name>.*` items and
+  /// `core.commentChar` from the git config of `repo`.
+  pub fn load(repo: &Repo) -> Result<Self> {
+    let mut config = TrailerConfig::default();
+
+    for (key, value) in repo.get_config_regexp(r"^trailer\.")? {
+      let Some(rest) = key.strip_prefix("trailer.") else {
+        continue;
+      };
+
+      if rest == "separators" {
+        if !value.is_empty() {
+          config.separators = value;
+        }
+        continue;
+      }
+
+      let Some((name, variable)) = rest.rsplit_once('.') else {
+        continue;
+      };
+      if !matches!(
+        variable,
+        "key" | "command" | "cmd" | "where" | "ifexists" | "ifmissing"
+      ) {
+        continue;
+      }
+
+      let item = if let Some(item) = config
+        .items
+        .iter_mut()
+        .find(|item| item.name.eq_ignore_ascii_case(name))
+      {
+        item
+      } else {
+        config.items.push(TrailerItem {
+          name: name.to_string(),
+          key: None,
+        });
+        config.items.last_mut().unwrap()
+      };
+      if variable == "key" {
+        item.key = Some(value);
+      }
+    }
+
+    // `auto` picks a comment character for commits only
+    if let Some(comment) = repo.get_config("core.commentChar")?
+      && comment != "auto"
+    {
+      config.comment = comment;
+    }
+
+    Ok(config)
+  }
+
+  /// Parses a single trailer `line`, which must not be a comment or start
+  /// with whitespace.
+  pub fn parse_line(&self, line: &str) -> Option<Trailer> {
+    if line.starts_with(self.comment.as_str()) {
+      return None;
+    }
+
+    let separator = self.find_separator(line).filter(|&pos| pos >= 1)?;
+    Some(self.parse_trailer(line, separator))
+  }
+
+  /// The trailers in the trailer block of `message`, like `git
+  /// interpret-trailers --parse` lists them.
+  pub fn parse(&self, message: &str) -> Vec<Trailer> {
+    self
+      .block_items(message)
+      .into_iter()
+      .filter_map(|item| item.trailer)
+      .collect()
+  }
+
+  /// Formats `trailer` with the configured key for its token, adding the
+  /// first separator unless the token already ends with one.
+  pub fn render(&self, trailer: &Trailer) -> String {
+    let token = self.key(&trailer.token);
+    let ends_with_separator = token
+      .trim_end_matches(is_space)
+      .chars()
+      .next_back()
+      .is_some_and(|c| self.separators.contains(c));
+
+    if ends_with_separator {
+      format!("{token}{}", trailer.value)
+    } else {
+      let separator = self.separators.chars().next().unwrap_or(':');
+      format!("{token}{separator} {}", trailer.value)
+    }
+  }
+
+  /// The token co-author trailers are written with
+  pub fn co_author_token(&self) -> &str {
+    self.key(CO_AUTHORED_BY)
+  }
+
+  /// Rewrites the trailers of `message` with `rewrite`, which returns the
+  /// replacement of a trailer or `None` to remove it. Unchanged trailers and
+  /// everything outside of the trailer block are kept as they are.
+  pub fn rewrite(
+    &self,
+    message: &str,
+    rewrite: impl FnMut(&Trailer) -> Option<Trailer>,
+  ) -> String {
+    self.rewrite_items(message, self.block_items(message), rewrite)
+  }
+
+  fn rewrite_items(
+    &self,
+    message: &str,
+    items: Vec<BlockItem>,
+    mut rewrite: impl FnMut(&Trailer) -> Option<Trailer>,
+  ) -> String {
+    let mut result = String::new();
+    let mut end = 0;
+
+    for item in items {
+      let Some(trailer) = item.trailer else {
+        continue;
+      };
+      let replacement = rewrite(&trailer);
+      if replacement.as_ref() == Some(&trailer) {
+        continue;
+      }
+
+      result.push_str(&message[end..item.range.start]);
+      end = item.range.end;
+
+      if let Some(replacement) = replacement {
+        let lines = &message[item.range];
+        let line_ending = if lines.ends_with("\r\n") {
+          "\r\n"
+        } else if lines.ends_with('\n') {
+          "\n"
+        } else {
+          ""
+        };
+        result.push_str(&self.render(&replacement));
+        result.push_str(line_ending);
+      }
+    }
+
+    result.push_str(&message[end..]);
+    result
+  }
+
+  /// Removes co-author trailers of a commit `message` that repeat an
+  /// earlier co-author's email, ignoring case. Co-authors in the paragraphs
+  /// before the trailer block count as well, e.g. ones typed below the
+  /// comments of the commit template. Returns the new message and the emails
+  /// of the removed trailers.
+  pub fn dedupe_co_authors(&self, message: &str) -> (String, Vec<String>) {
+    let mut seen: Vec<String> = Vec::new();
+    let mut removed = Vec::new();
+
+    let message =
+      self.rewrite_items(message, self.body_items(message), |trailer| {
+        if let Some((_, email)) =
+          trailer.contact().filter(|_| trailer.is_co_author())
+        {
+          let canonical = canonical_email(email);
+          if seen.contains(&canonical) {
+            removed.push(email.trim().to_string());
+            return None;
+          }
+          seen.push(canonical);
+        }
+        Some(trailer.clone())
+      });
+
+    (message, removed)
+  }
+
+  /// The lines of the trailer block of `message`, with continuation lines
+  /// joined to the trailer before them.
+  fn block_items(&self, message: &str) -> Vec<BlockItem> {
+    let end = self.end_of_message(message);
+    let start = self.start_of_block(message, end);
+    self.items_between(message, start, end)
+  }
+
+  /// The lines of `message` after the title as trailers where they parse as
+  /// one, with the trailer block parsed like [`TrailerConfig::block_items`].
+  fn body_items(&self, message: &str) -> Vec<BlockItem> {
+    let end = self.end_of_message(message);
+    let start = self.start_of_block(message, end);
+    let end_of_title = self.end_of_title(&message[..end]);
+
+    let mut items: Vec<BlockItem> = lines(&message[..start])
+      .skip_while(|(bol, _)| *bol < end_of_title)
+      .map(|(bol, line)| BlockItem {
+        range: bol..bol + line.len(),
+        trailer: self.parse_line(line),
+      })
+      .collect();
+    items.extend(self.items_between(message, start, end));
+    items
+  }
+
+  /// The lines of `message[start..end]` with continuation lines joined to
+  /// the trailer before them.
+  fn items_between(
+    &self,
+    message: &str,
+    start: usize,
+    end: usize,
+  ) -> Vec<BlockItem> {
+    let mut items: Vec<BlockItem> = Vec::new();
+    let mut last_is_trailer = false;
+
+    for (offset, line) in lines(&message[start..end]) {
+      let range = start + offset..start + offset + line.len();
+
+      if last_is_trailer && line.starts_with(is_space) {
+        items.last_mut().unwrap().range.end = range.end;
+        continue;
+      }
+
+      last_is_trailer = self.find_separator(line).is_some_and(|pos| pos >= 1);
+      items.push(BlockItem {
+        range,
+        trailer: None,
+      });
+    }
+
+    for item in &mut items {
+      let text = &message[item.range.clone()];
+      if text.starts_with(self.comment.as_str()) {
+        continue;
+      }
+      if let Some(separator) = self.find_separator(text).filter(|&pos| pos >= 1)
+      {
+        item.trailer = Some(self.parse_trailer(text, separator));
+      }
+    }
+
+    items
+  }
+
+  /// The end of the part of `message` that can hold trailers: before a
+  /// patch, the scissors line and trailing comments and blank lines.
+  fn end_of_message(&self, message: &str) -> usize {
+    let len = find_patch_start(message);
+    let cutoff = self.find_scissors(message).map_or(len, |pos| pos.min(len));
+
+    // Git tracks the start of the trailing comments with 0 for none
+    let mut start_of_comments = 0;
+    let mut in_conflicts = false;
+
+    for (bol, line) in lines(&message[..cutoff]) {
+      if line.starts_with(self.comment.as_str()) || line.starts_with('\n') {
+        if start_of_comments == 0 {
+          start_of_comments = bol;
+        }
+      } else if line.starts_with("Conflicts:\n") {
+        in_conflicts = true;
+        if start_of_comments == 0 {
+          start_of_comments = bol;
+        }
+      } else if in_conflicts && line.starts_with('\t') {
+        // A path of the conflicts block
+      } else if start_of_comments != 0 {
+        start_of_comments = 0;
+        in_conflicts = false;
+      }
+    }
+
+    if start_of_comments == 0 {
+      cutoff
+    } else {
+      start_of_comments
+    }
+  }
+
+  /// The start of the scissors line in `message`, if there is one
+  fn find_scissors(&self, message: &str) -> Option<usize> {
+    let pattern = format!("\n{} {CUT_LINE}", self.comment);
+
+    if message.starts_with(&pattern[1..]) {
+      return Some(0);
+    }
+    message.find(&pattern).map(|pos| pos + 1)
+  }
+
+  /// The start of the trailer block in `message[..end]`, which is `end` if
+  /// there is none. The block is the last paragraph, unless it is the title,
+  /// and must consist of trailers mostly.
+  fn start_of_block(&self, message: &str, end: usize) -> usize {
+    let text = &message[..end];
+    let end_of_title = self.end_of_title(text);
+
+    let mut only_spaces = true;
+    let mut recognized_prefix = false;
+    let mut trailer_lines = 0;
+    let mut non_trailer_lines = 0;
+    let mut possible_continuation_lines = 0;
+
+    for (bol, line) in lines(text).collect::<Vec<_>>().into_iter().rev() {
+      if bol < end_of_title {
+        break;
+      }
+
+      if line.starts_with(self.comment.as_str()) {
+        non_trailer_lines += possible_continuation_lines;
+        possible_continuation_lines = 0;
+        continue;
+      }
+
+      if is_blank_line(line) {
+        if only_spaces {
+          continue;
+        }
+        non_trailer_lines += possible_continuation_lines;
+        if (recognized_prefix && trailer_lines * 3 >= non_trailer_lines)
+          || (trailer_lines > 0 && non_trailer_lines == 0)
+        {
+          return bol + line.len();
+        }
+        return end;
+      }
+      only_spaces = false;
+
+      if GIT_GENERATED_PREFIXES
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+      {
+        trailer_lines += 1;
+        possible_continuation_lines = 0;
+        recognized_prefix = true;
+        continue;
+      }
+
+      match self.find_separator(line) {
+        Some(separator) if separator >= 1 && !line.starts_with(is_space) => {
+          trailer_lines += 1;
+          possible_continuation_lines = 0;
+          recognized_prefix |= self.items.iter().any(|item| {
+            token_matches(&line[..separator], &item.name)
+              || item
+                .key
+                .as_ref()
+                .is_some_and(|key| token_matches(&line[..separator], key))
+          });
+        }
+        _ if line.starts_with(is_space) => possible_continuation_lines += 1,
+        _ => {
+          non_trailer_lines += 1 + possible_continuation_lines;
+          possible_continuation_lines = 0;
+        }
+      }
+    }
+
+    end
+  }
+
+  /// The end of the first paragraph of `text`, the title, which can't be
+  /// trailers
+  fn end_of_title(&self, text: &str) -> usize {
+    lines(text)
+      .filter(|(_, line)| !line.starts_with(self.comment.as_str()))
+      .find(|(_, line)| is_blank_line(line))
+      .map_or(text.len(), |(bol, _)| bol)
+  }
+
+  /// The position of the separator in `line`, which has to follow a token
+  /// of alphanumeric characters and dashes, optionally with whitespace
+  /// before the separator.
+  fn find_separator(&self, line: &str) -> Option<usize> {
+    let mut whitespace_found = false;
+
+    for (pos, c) in line.char_indices() {
+      if self.separators.contains(c) {
+        return Some(pos);
+      }
+      if !whitespace_found && (c.is_ascii_alphanumeric() || c == '-') {
+        continue;
+      }
+      if pos > 0 && (c == ' ' || c == '\t') {
+        whitespace_found = true;
+        continue;
+      }
+      break;
+    }
+
+    None
+  }
+
+  /// Splits `text` at the `separator`, unfolds the value and replaces the
+  /// token with the key of the first configured item it matches.
+  fn parse_trailer(&self, text: &str, separator: usize) -> Trailer {
+    let token = text[..separator].trim_matches(is_space);
+    let value = unfold(text[separator + 1..].trim_matches(is_space));
+
+    Trailer {
+      token: self.key(token).to_string(),
+      value,
+    }
+  }
+
+  /// The token written for `token`, the key of the `trailer.<name>` item it
+  /// matches if that has one.
+  fn key<'a>(&'a self, token: &'a str) -> &'a str {
+    self
+      .items
+      .iter()
+      .find(|item| item.matches(token))
+      .and_then(|item| item.key.as_deref())
+      .unwrap_or(token)
+  }
+}
+
+/// Checks if `token` is a prefix of `name`, ignoring case
+fn token_matches(token: &str, name: &str) -> bool {
+  name
+    .as_bytes()
+    .get(..token.len())
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(token.as_bytes()))
+}
+
+/// The lines of `text` with their offsets, including the line endings
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  text.split_inclusive('\n').scan(0, |offset, line| {
+    let bol = *offset;
+    *offset += line.len();
+    Some((bol, line))
+  })
+}
+
+/// The start of the patch in a message prepared by `git format-patch`
+fn find_patch_start(message: &str) -> usize {
+  lines(message)
+    .find(|(_, line)| {
+      line
+        .strip_prefix("---")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(is_space)
+    })
+    .map_or(message.len(), |(bol, _)| bol)
+}
+
+fn is_blank_line(line: &str) -> bool {
+  line.chars().all(is_space)
+}
+
+/// Whitespace as git defines it
+fn is_space(c: char) -> bool {
+  matches!(c, ' ' | '\t' | '\n' | '\r')
+}
+
+/// Joins continuation lines with a single space.
+fn unfold(value: &str) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut chars = value.chars().peekable();
+
+  while let Some(c) = chars.next() {
+    if c == '\n' {
+      while chars.next_if(|&c| is_space(c)).is_some() {}
+      result.push(' ');
+    } else {
+      result.push(c);
+    }
+  }
+
+  result.trim_matches(is_space).to_string()
+}
+
+/// The form of `email` used to compare co-authors
+pub fn canonical_email(email: &str) -> String {
+  email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::process::Command;
+
+  use proptest::prelude::*;
+
+  use super::*;
+
+  fn config(separators: &str, items: &[(&str, Option<&str>)]) -> TrailerConfig {
+    TrailerConfig {
+      separators: separators.to_string(),
+      items: items
+        .iter()
+        .map(|(name, key)| TrailerItem {
+          name: (*name).to_string(),
+          key: key.map(str::to_string),
+        })
+        .collect(),
+      ..TrailerConfig::default()
+    }
+  }
+
+  #[test]
+  fn test_parse() {
+    let message = "Subject\n\n\
+                   Body: not a trailer\n\
+                   with more text\n\n\
+                   co-authored-by  :  Peter <Pan> <peter.pan@example.com>\n\
+                   Co-authored-by: Wendy\n  Darling <wendy@example.com>\n\
+                   # Co-authored-by: Ignored <comment@example.com>\n\
+                   Bug #42\n\
+                   \n# Please enter the commit message\n";
+
+    assert_eq!(
+      config(":#", &[]).parse(message),
+      vec![
+        Trailer {
+          token: "co-authored-by".to_string(),
+          value: "Peter <Pan> <peter.pan@example.com>".to_string(),
+        },
+        Trailer::co_author("Wendy Darling", "wendy@example.com"),
+        Trailer {
+          token: "Bug".to_string(),
+          value: "42".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_no_block() {
+    let config = TrailerConfig::default();
+
+    assert!(config.parse("Co-authored-by: A <a@b>\n").is_empty());
+    assert!(
+      config
+        .parse("Subject\n\nText\nFoo: a\nMore text\n")
+        .is_empty()
+    );
+    assert!(config.parse("Subject\n\nFoo: a\n---\nBar: b\n").contains(
+      &Trailer {
+        token: "Foo".to_string(),
+        value: "a".to_string(),
+      }
+    ));
+    assert!(config.parse("Subject\n\nX-y_z: a\nA b: c\n").is_empty());
+  }
+
+  #[test]
+  fn test_items() {
+    let config = config(":", &[("co", Some("Co-authored-by"))]);
+
+    assert_eq!(
+      config.parse("Subject\n\nCO: Peter Pan <peter.pan@example.com>\n"),
+      vec![Trailer::co_author("Peter Pan", "peter.pan@example.com")]
+    );
+  }
+
+  #[test]
+  fn test_contact() {
+    assert_eq!(
+      Trailer::co_author("Peter <Pan>", "p@x").contact(),
+      Some(("Peter <Pan>", "p@x"))
+    );
+    assert_eq!(Trailer::co_author("", "p@x").contact(), Some(("", "p@x")));
+    assert_eq!(
+      Trailer {
+        token: CO_AUTHORED_BY.to_string(),
+        value: "Wendy Darling".to_string(),
+      }
+      .contact(),
+      None
+    );
+  }
+
+  #[test]
+  fn test_render() {
+    let config = config(":#", &[]);
+
+    assert_eq!(
+      config.render(&Trailer {
+        token: "Bug #".to_string(),
+        value: "42".to_string(),
+      }),
+      "Bug #42"
+    );
+    assert_eq!(
+      config.render(&Trailer::co_author("Peter Pan", "p@x")),
+      "Co-authored-by: Peter Pan <p@x>"
+    );
+  }
+
+  #[test]
+  fn test_render_key() {
+    // The configured key and the first separator are written
+    let config = config("=:", &[("co", Some("Co-Authored-By"))]);
+
+    assert_eq!(
+      config.render(&Trailer::co_author("Peter Pan", "p@x")),
+      "Co-Authored-By= Peter Pan <p@x>"
+    );
+    assert_eq!(config.co_author_token(), "Co-Authored-By");
+  }
+
+  #[test]
+  fn test_dedupe_co_authors() {
+    let message = "Subject\n\n\
+                   Co-authored-by: Peter Pan <peter.pan@example.com>\n\
+                   co-authored-by: Peter Pan <PETER.PAN@example.com>\n\
+                   Co-authored-by: Wendy Darling <wendy.darling@example.com>\n\
+                   # Co-authored-by: Peter Pan <peter.pan@example.com>\n\
+                   Co-authored-by: Peter\n \
+                   <peter.pan@example.com>\n";
+
+    assert_eq!(
+      TrailerConfig::default().dedupe_co_authors(message),
+      (
+        "Subject\n\n\
+         Co-authored-by: Peter Pan <peter.pan@example.com>\n\
+         Co-authored-by: Wendy Darling <wendy.darling@example.com>\n\
+         # Co-authored-by: Peter Pan <peter.pan@example.com>\n"
+          .to_string(),
+        vec![
+          "PETER.PAN@example.com".to_string(),
+          "peter.pan@example.com".to_string()
+        ]
+      )
+    );
+  }
+
+  #[test]
+  fn test_dedupe_co_authors_unchanged() {
+    let message =
+      "Subject\n\nCo-authored-by: Peter Pan <peter.pan@example.com>";
+
+    assert_eq!(
+      TrailerConfig::default().dedupe_co_authors(message),
+      (message.to_string(), vec![])
+    );
+  }
+
+  /// The output of `git interpret-trailers --parse` for `message` with the
+  /// trailer settings of `config`.
+  fn git_parse(config: &TrailerConfig, message: &str) -> String {
+    let mut cmd = Command::new("git");
+    cmd
+      .args(["-c", &format!("trailer.separators={}", config.separators)])
+      .args(["-c", &format!("core.commentChar={}", config.comment)]);
+    for item in &config.items {
+      match &item.key {
+        Some(key) => {
+          cmd.args(["-c", &format!("trailer.{}.key={key}", item.name)])
+        }
+        None => cmd.args(["-c", &format!("trailer.{}.where=end", item.name)]),
+      };
+    }
+
+    let mut child = cmd
+      .args(["interpret-trailers", "--parse"])
+      .stdin(std::process::Stdio::piped())
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap();
+    std::io::Write::write_all(
+      child.stdin.as_mut().unwrap(),
+      message.as_bytes(),
+    )
+    .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "git failed: {output:?}");
+
+    String::from_utf8(output.stdout).unwrap()
+  }
+
+  /// The trailers formatted like `git interpret-trailers --parse` prints
+  /// them
+  fn render_lines(config: &TrailerConfig, trailers: &[Trailer]) -> String {
+    let mut lines = String::new();
+    for trailer in trailers {
+      lines.push_str(&config.render(trailer));
+      lines.push('\n');
+    }
+    lines
+  }
+
+  fn arb_config() -> impl Strategy<Value = TrailerConfig> {
+    (
+      prop::sample::select(vec![":", ":#", "=:", "#"]),
+      prop::sample::select(vec!["#", ";"]),
+      prop::sample::subsequence(vec!["co", "sign", "Bug", "fixes"], 0..3)
+        .prop_flat_map(|names| {
+          names
+            .into_iter()
+            .map(|name| {
+              (
+                Just(name),
+                prop::option::of(prop::sample::select(vec![
+                  "Co-authored-by",
+                  "Signed-off-by: ",
+                  "Fixes #",
+                ])),
+              )
+            })
+            .collect::<Vec<_>>()
+        }),
+    )
+      .prop_map(|(separators, comment, items)| TrailerConfig {
+        comment: comment.to_string(),
+        ..config(separators, &items)
+      })
+  }
+
+  /// Lines that are trailers, continuations, comments or text in various
+  /// spellings
+  fn arb_line() -> impl Strategy<Value = String> {
+    let token = prop::sample::select(vec![
+      "Co-authored-by",
+      "co-authored-by",
+      "CO-AUTHORED-BY",
+      "co",
+      "Si",
+      "Signed-off-by",
+      "Bug",
+      "fix",
+      "X-y_z",
+      "A b",
+      "-",
+    ]);
+    let separator = prop::sample::select(vec![":", "=", "#", " :", "\t: "]);
+    let value = "[a-zA-Z0-9 <>@.:#=-]{0,20}";
+
+    prop_oneof![
+      4 => (token, separator, value)
+        .prop_map(|(token, separator, value)| format!("{token}{separator}{value}")),
+      2 => ("[ \t]{1,3}", value).prop_map(|(indent, value)| format!("{indent}{value}")),
+      1 => value,
+      1 => prop::sample::select(vec!["# comment", "; comment", "", "  ", "---", "--- x"])
+        .prop_map(str::to_string),
+      1 => Just("Signed-off-by: Peter Pan <peter.pan@example.com>".to_string()),
+    ]
+  }
+
+  fn arb_message() -> impl Strategy<Value = String> {
+    (
+      "[a-zA-Z ]{1,10}",
+      prop::collection::vec(arb_line(), 0..4),
+      prop::collection::vec(arb_line(), 0..6),
+      prop::sample::select(vec!["", "\n", "\n\n# comment\n", "\r\n"]),
+    )
+      .prop_map(|(subject, body, trailers, end)| {
+        let mut message = format!("{subject}\n\n");
+        if !body.is_empty() {
+          message.push_str(&body.join("\n"));
+          message.push_str("\n\n");
+        }
+        message.push_str(&trailers.join("\n"));
+        message.push_str(end);
+        message
+      })
+  }
+
+  proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn test_parse_like_git(config in arb_config(), message in arb_message()) {
+      let rendered = render_lines(&config, &config.parse(&message));
+
+      prop_assert_eq!(rendered, git_parse(&config, &message));
+    }
+
+    #[test]
+    fn test_co_author_round_trip(
+      config in arb_config(),
+      contacts in prop::collection::vec(
+        ("[a-zA-Z<][a-zA-Z<> .]{0,10}[a-zA-Z.]", "[a-z.+]{1,8}@[a-z.]{1,8}"),
+        1..4,
+      ),
+    ) {
+      let trailers: Vec<Trailer> = contacts
+        .iter()
+        .map(|(name, email)| Trailer::co_author(name, email))
+        .collect();
+      let rendered = render_lines(&config, &trailers);
+      let message = format!("Subject\n\n{rendered}");
+
+      prop_assert_eq!(git_parse(&config, &message), rendered);
+      prop_assert_eq!(config.parse(&message), trailers);
+    }
+  }
+}
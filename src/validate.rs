@@ -0,0 +1,181 @@
+use anyhow::Result;
+
+// Characters allowed in the local part of an email address besides
+// alphanumerics (RFC 5322 `atext`)
+const LOCAL_PART_SPECIALS: &str = "!#$%&'*+/=?^_`{|}~-.";
+
+pub fn validate_alias(alias: &str) -> Result<String> {
+  if alias.is_empty() {
+    anyhow::bail!("Alias must not be empty");
+  }
+
+  if alias.chars().any(|c| c.is_whitespace() || c.is_control()) {
+    anyhow::bail!(
+      "Alias '{}' must not contain whitespace or control characters",
+      alias.escape_debug()
+    );
+  }
+
+  Ok(alias.to_string())
+}
+
+pub fn validate_name(name: &str) -> Result<String> {
+  let name = name.trim();
+
+  if name.is_empty() {
+    anyhow::bail!("Name must not be empty");
+  }
+
+  if name.chars().any(char::is_control) {
+    anyhow::bail!(
+      "Name '{}' must not contain control characters",
+      name.escape_debug()
+    );
+  }
+
+  if name.contains(['<', '>']) {
+    anyhow::bail!("Name '{name}' must not contain '<' or '>'");
+  }
+
+  Ok(name.to_string())
+}
+
+pub fn validate_github_login(login: &str) -> Result<String> {
+  let login = login.trim();
+
+  if login.is_empty()
+    || login.len() > 39
+    || login.starts_with('-')
+    || login.ends_with('-')
+    || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+  {
+    anyhow::bail!("'{}' is not a valid GitHub login", login.escape_debug());
+  }
+
+  Ok(login.to_string())
+}
+
+/// Validates an email address and returns it in its normalised form with the
+/// domain converted to lowercase ASCII (punycode for internationalised
+/// domains).
+pub fn normalize_email(email: &str) -> Result<String> {
+  let email = email.trim();
+
+  if email.chars().any(|c| c.is_whitespace() || c.is_control()) {
+    anyhow::bail!(
+      "Email '{}' must not contain whitespace or control characters",
+      email.escape_debug()
+    );
+  }
+
+  let Some((local, domain)) = email.rsplit_once('@') else {
+    anyhow::bail!("Email '{email}' is missing an '@'");
+  };
+
+  validate_local_part(local)
+    .map_err(|e| anyhow::anyhow!("Email '{email}' is invalid: {e}"))?;
+
+  let domain = normalize_domain(domain)
+    .map_err(|e| anyhow::anyhow!("Email '{email}' is invalid: {e}"))?;
+
+  Ok(format!("{local}@{domain}"))
+}
+
+fn validate_local_part(local: &str) -> Result<()> {
+  if local.is_empty() {
+    anyhow::bail!("local part is empty");
+  }
+
+  if local.len() > 64 {
+    anyhow::bail!("local part is longer than 64 characters");
+  }
+
+  if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+    anyhow::bail!("local part has misplaced dots");
+  }
+
+  if let Some(c) = local
+    .chars()
+    .find(|c| !(c.is_alphanumeric() || LOCAL_PART_SPECIALS.contains(*c)))
+  {
+    anyhow::bail!("local part contains invalid character '{c}'");
+  }
+
+  Ok(())
+}
+
+fn normalize_domain(domain: &str) -> Result<String> {
+  let ascii = idna::domain_to_ascii(domain)
+    .map_err(|_| anyhow::anyhow!("domain '{domain}' is not a valid domain"))?;
+
+  if ascii.is_empty() || ascii.len() > 253 {
+    anyhow::bail!("domain '{domain}' has an invalid length");
+  }
+
+  if !ascii.contains('.') {
+    anyhow::bail!("domain '{domain}' is not fully qualified");
+  }
+
+  for label in ascii.split('.') {
+    if label.is_empty()
+      || label.len() > 63
+      || label.starts_with('-')
+      || label.ends_with('-')
+      || !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+      anyhow::bail!("domain '{domain}' contains an invalid label");
+    }
+  }
+
+  Ok(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+  use test_case::test_case;
+
+  use super::*;
+
+  #[test_case("peter.pan@example.com", "peter.pan@example.com")]
+  #[test_case(" peter@Example.COM ", "peter@example.com")]
+  #[test_case("peter+git@sub.example.com", "peter+git@sub.example.com")]
+  #[test_case("peter@bücher.de", "peter@xn--bcher-kva.de")]
+  fn test_normalize_email_valid(input: &str, expected: &str) {
+    assert_eq!(normalize_email(input).unwrap(), expected);
+  }
+
+  #[test_case("peter.pan"; "missing at")]
+  #[test_case("@example.com"; "empty local part")]
+  #[test_case("peter@localhost"; "unqualified domain")]
+  #[test_case("peter..pan@example.com"; "consecutive dots")]
+  #[test_case("peter>@example.com"; "angle bracket")]
+  #[test_case("peter@exa mple.com"; "whitespace")]
+  #[test_case("peter@example.com\nCo-authored-by: x <y@z.de>"; "newline")]
+  #[test_case("peter@-example.com"; "leading hyphen")]
+  fn test_normalize_email_invalid(input: &str) {
+    assert!(normalize_email(input).is_err());
+  }
+
+  #[test_case("Peter Pan", true; "plain")]
+  #[test_case("  Peter Pan ", true; "surrounding whitespace")]
+  #[test_case("", false; "empty")]
+  #[test_case("Peter <Pan>", false; "angle brackets")]
+  #[test_case("Peter\nPan", false; "newline")]
+  fn test_validate_name(input: &str, valid: bool) {
+    assert_eq!(validate_name(input).is_ok(), valid);
+  }
+
+  #[test_case("peter-pan", true; "plain")]
+  #[test_case("-peter", false; "leading hyphen")]
+  #[test_case("peter pan", false; "whitespace")]
+  fn test_validate_github_login(input: &str, valid: bool) {
+    assert_eq!(validate_github_login(input).is_ok(), valid);
+  }
+
+  #[test_case("pp", true; "plain")]
+  #[test_case("", false; "empty")]
+  #[test_case("p p", false; "whitespace")]
+  fn test_validate_alias(input: &str, valid: bool) {
+    assert_eq!(validate_alias(input).is_ok(), valid);
+  }
+}
@@ -0,0 +1,223 @@
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+  buddy::Buddies,
+  git::{Commit, Repo},
+  lint,
+  policy::Policy,
+  trailer::{Trailer, TrailerConfig},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+  /// Human readable output
+  #[default]
+  Human,
+  /// A JSON array with one record per commit
+  Json,
+  /// A `JUnit` XML test suite with one test case per commit
+  Junit,
+}
+
+/// The result of verifying a single commit
+#[derive(Debug, Serialize)]
+pub struct CommitReport {
+  pub sha: String,
+  pub subject: String,
+  pub problems: Vec<String>,
+}
+
+/// Checks the co-author trailers of all commits in `range` with the policy
+/// of `repo`, or the default policy if it has none. With `mailmap` the
+/// co-authors are mapped to their canonical form first.
+pub fn verify(
+  repo: &Repo,
+  buddies: &Buddies,
+  range: &str,
+  mailmap: bool,
+) -> Result<Vec<CommitReport>> {
+  let policy = Policy::load(repo)?.unwrap_or_default();
+  let config = TrailerConfig::load(repo)?;
+
+  repo
+    .commits(range)?
+    .into_iter()
+    .map(|commit| {
+      let message = if mailmap {
+        apply_mailmap(repo, &commit, &config)?
+      } else {
+        commit.message
+      };
+
+      // Without the mailmap the author is only recognized by the same email
+      let trailers = config.parse(&message);
+      let committer = if mailmap {
+        let emails = lint::co_author_emails(&trailers);
+        repo.emails_of(&commit.author_email, &emails)?
+      } else {
+        vec![commit.author_email.as_str()]
+      };
+
+      Ok(CommitReport {
+        problems: lint::lint_message(
+          &message, buddies, &policy, &committer, &config,
+        ),
+        sha: commit.sha,
+        subject: commit.subject,
+      })
+    })
+    .collect()
+}
+
+/// Replaces the contacts of all well-formed co-author trailers in the
+/// message of `commit` with their canonical form from the mailmap.
+fn apply_mailmap(
+  repo: &Repo,
+  commit: &Commit,
+  config: &TrailerConfig,
+) -> Result<String> {
+  let contact = |trailer: &Trailer| {
+    trailer
+      .contact()
+      .filter(|(name, _)| trailer.is_co_author() && !name.is_empty())
+      .map(|(name, email)| format!("{name} <{email}>"))
+  };
+
+  let contacts: Vec<String> = config
+    .parse(&commit.message)
+    .iter()
+    .filter_map(contact)
+    .collect();
+  let mut mapped = repo
+    .check_mailmap(&contacts.iter().map(String::as_str).collect::<Vec<_>>())?
+    .into_iter();
+
+  Ok(config.rewrite(&commit.message, |trailer| {
+    let mut trailer = trailer.clone();
+    if contact(&trailer).is_some()
+      && let Some(value) = mapped.next()
+    {
+      trailer.value = value;
+    }
+    Some(trailer)
+  }))
+}
+
+/// Renders the `reports` of `range` in `format`.
+pub fn render(
+  format: ReportFormat,
+  range: &str,
+  reports: &[CommitReport],
+) -> Result<String> {
+  let mut out = String::new();
+  let failures = reports.iter().filter(|r| !r.problems.is_empty()).count();
+
+  match format {
+    ReportFormat::Human => {
+      for report in reports {
+        let short = report.sha.get(..7).unwrap_or(&report.sha);
+        if report.problems.is_empty() {
+          writeln!(out, "ok     {short} {}", report.subject)?;
+        } else {
+          writeln!(out, "FAILED {short} {}", report.subject)?;
+          for problem in &report.problems {
+            writeln!(out, "  - {problem}")?;
+          }
+        }
+      }
+      writeln!(
+        out,
+        "Verified {} commit(s) in {range}, {failures} failed.",
+        reports.len()
+      )?;
+    }
+    ReportFormat::Json => {
+      out = serde_json::to_string_pretty(reports)
+        .context("Failed to serialize report")?;
+      out.push('\n');
+    }
+    ReportFormat::Junit => {
+      writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+      writeln!(
+        out,
+        r#"<testsuite name="git-squad verify {}" tests="{}" failures="{failures}">"#,
+        xml_escape(range),
+        reports.len(),
+      )?;
+      for report in reports {
+        let name = xml_escape(&format!("{} {}", report.sha, report.subject));
+        if report.problems.is_empty() {
+          writeln!(
+            out,
+            r#"  <testcase classname="co-authors" name="{name}"/>"#
+          )?;
+        } else {
+          writeln!(
+            out,
+            r#"  <testcase classname="co-authors" name="{name}">"#
+          )?;
+          writeln!(
+            out,
+            r#"    <failure message="{} problem(s)">{}</failure>"#,
+            report.problems.len(),
+            xml_escape(&report.problems.join("\n"))
+          )?;
+          writeln!(out, "  </testcase>")?;
+        }
+      }
+      writeln!(out, "</testsuite>")?;
+    }
+  }
+
+  Ok(out)
+}
+
+fn xml_escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+    .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render_junit() {
+    let reports = vec![
+      CommitReport {
+        sha: "1234567".to_string(),
+        subject: "Fix <the> clock".to_string(),
+        problems: vec![],
+      },
+      CommitReport {
+        sha: "89abcde".to_string(),
+        subject: "Sail away".to_string(),
+        problems: vec![
+          "Unknown co-author 'hook@jolly-roger.sea'".to_string(),
+          "Duplicate co-author 'peter.pan@example.com'".to_string(),
+        ],
+      },
+    ];
+
+    assert_eq!(
+      render(ReportFormat::Junit, "main..HEAD", &reports).unwrap(),
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="git-squad verify main..HEAD" tests="2" failures="1">
+  <testcase classname="co-authors" name="1234567 Fix &lt;the&gt; clock"/>
+  <testcase classname="co-authors" name="89abcde Sail away">
+    <failure message="2 problem(s)">Unknown co-author &apos;hook@jolly-roger.sea&apos;
+Duplicate co-author &apos;peter.pan@example.com&apos;</failure>
+  </testcase>
+</testsuite>
+"#
+    );
+  }
+}
@@ -0,0 +1,98 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+// How deep to look for repositories below a workspace directory
+const MAX_DEPTH: usize = 3;
+
+/// A repository that is part of a workspace
+#[derive(Debug, PartialEq, Eq)]
+pub struct WorkspaceRepo {
+  /// The path relative to the workspace used in messages
+  pub name: String,
+  pub path: PathBuf,
+}
+
+/// Finds the repositories of a workspace. `workspace` is either a directory
+/// that is searched for repositories or a file listing one repository per
+/// line, relative to the file. Empty lines and lines starting with `#` are
+/// ignored.
+pub fn discover(workspace: &Path) -> Result<Vec<WorkspaceRepo>> {
+  if workspace.is_file() {
+    return read_list(workspace);
+  }
+
+  if !workspace.is_dir() {
+    anyhow::bail!("Workspace '{}' doesn't exist", workspace.display());
+  }
+
+  let mut repos = Vec::new();
+  find_repos(workspace, workspace, 0, &mut repos)?;
+  repos.sort_by(|a, b| a.name.cmp(&b.name));
+
+  Ok(repos)
+}
+
+fn read_list(file: &Path) -> Result<Vec<WorkspaceRepo>> {
+  let contents = fs::read_to_string(file).with_context(|| {
+    format!("Failed to read workspace file '{}'", file.display())
+  })?;
+  let base = file.parent().unwrap_or(Path::new("."));
+
+  Ok(
+    contents
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(|line| WorkspaceRepo {
+        name: line.to_string(),
+        path: base.join(line),
+      })
+      .collect(),
+  )
+}
+
+/// Collects the repositories at or below `dir` without descending into
+/// repositories or hidden directories.
+fn find_repos(
+  root: &Path,
+  dir: &Path,
+  depth: usize,
+  repos: &mut Vec<WorkspaceRepo>,
+) -> Result<()> {
+  if dir.join(".git").exists() {
+    let name = dir.strip_prefix(root).unwrap_or(dir);
+    let name = if name.as_os_str().is_empty() {
+      ".".to_string()
+    } else {
+      name.display().to_string()
+    };
+
+    repos.push(WorkspaceRepo {
+      name,
+      path: dir.to_path_buf(),
+    });
+    return Ok(());
+  }
+
+  if depth >= MAX_DEPTH {
+    return Ok(());
+  }
+
+  let entries = fs::read_dir(dir)
+    .with_context(|| format!("Failed to read directory '{}'", dir.display()))?;
+
+  for entry in entries {
+    let entry = entry?;
+    let hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+    if !hidden && entry.file_type()?.is_dir() {
+      find_repos(root, &entry.path(), depth + 1, repos)?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,51 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use similar::TextDiff;
+
+/// Writes `contents` to `path`. With `dry_run` nothing is written and a
+/// unified diff of the change is printed instead.
+pub fn write_file(path: &Path, contents: &str, dry_run: bool) -> Result<()> {
+  if dry_run {
+    let old = if path.exists() {
+      fs::read_to_string(path)
+        .with_context(|| format!("Failed to read '{}'", path.display()))?
+    } else {
+      String::new()
+    };
+
+    print!("{}", unified_diff(path, &old, contents));
+    return Ok(());
+  }
+
+  fs::write(path, contents)
+    .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// A unified diff from `old` to `new` with `path` in the headers. Empty if
+/// nothing changed.
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+  let path = path.display().to_string();
+
+  TextDiff::from_lines(old, new)
+    .unified_diff()
+    .header(&path, &path)
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_unified_diff() {
+    let path = Path::new("template.txt");
+
+    assert_eq!(
+      unified_diff(path, "Subject\n", "Subject\n\nCo-authored-by: pp\n"),
+      "--- template.txt\n+++ template.txt\n@@ -1 +1,3 @@\n Subject\n+\n\
+       +Co-authored-by: pp\n"
+    );
+    assert_eq!(unified_diff(path, "Subject\n", "Subject\n"), "");
+  }
+}
@@ -16,6 +16,16 @@ pub struct TestBed {
   buddies_file: NamedTempFile,
 }
 
+impl TestBed {
+  pub fn path(&self) -> &Path {
+    self.test_dir.path()
+  }
+
+  pub fn buddies_path(&self) -> &Path {
+    self.buddies_file.path()
+  }
+}
+
 pub fn setup_git_repo() -> Result<TestBed, Box<dyn std::error::Error>> {
   let repo_dir = tempdir()?;
   let repo_path = repo_dir.path();
@@ -51,6 +61,44 @@ pub fn setup_git_repo() -> Result<TestBed, Box<dyn std::error::Error>> {
   })
 }
 
+/// Runs git with `args` in the test repository, failing the test if git
+/// fails. Returns what git printed to stderr, where it writes its messages and
+/// the output of hooks.
+pub fn git(
+  test_bed: &TestBed,
+  args: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+  git_with_env(test_bed, args, &[])
+}
+
+/// Runs git like [`git`] with the additional environment variables `envs`.
+pub fn git_with_env(
+  test_bed: &TestBed,
+  args: &[&str],
+  envs: &[(&str, &str)],
+) -> Result<String, Box<dyn std::error::Error>> {
+  let output = Command::new("git")
+    .args(args)
+    .envs(envs.iter().copied())
+    .current_dir(test_bed.path())
+    .output()?;
+  assert!(output.status.success(), "git {args:?} failed: {output:?}");
+  Ok(String::from_utf8(output.stderr)?)
+}
+
+/// Makes an empty commit with `message` at a fixed committer date.
+pub fn commit(
+  test_bed: &TestBed,
+  message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  git_with_env(
+    test_bed,
+    &["commit", "--allow-empty", "-m", message],
+    &[("GIT_COMMITTER_DATE", "2025-01-01T12:00:00+01:00")],
+  )?;
+  Ok(())
+}
+
 pub fn read_commit_template(
   test_bed: &TestBed,
 ) -> Result<String, Box<dyn std::error::Error>> {
@@ -61,7 +109,7 @@ pub fn read_commit_template(
     .output()?;
 
   let template_path = String::from_utf8(output.stdout)?.trim().to_string();
-  slurp(template_path)
+  slurp(test_bed.path().join(template_path))
 }
 
 pub fn read_buddies_file(
@@ -71,6 +119,14 @@ pub fn read_buddies_file(
   slurp(buddies_path)
 }
 
+pub fn write_buddies_file(
+  test_bed: &TestBed,
+  contents: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  fs::write(test_bed.buddies_file.path(), contents)?;
+  Ok(())
+}
+
 pub fn spawn_test_command<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
   test_bed: &TestBed,
   args: I,
@@ -91,7 +147,9 @@ pub fn make_test_command<I: IntoIterator<Item = S>, S: AsRef<OsStr>>(
   cmd
     .args(["--buddies-file", &buddies_path])
     .args(args)
-    .current_dir(&test_dir_path);
+    .current_dir(&test_dir_path)
+    .env_remove("RUST_BACKTRACE")
+    .env_remove("RUST_LIB_BACKTRACE");
 
   Ok(cmd)
 }
@@ -118,12 +176,7 @@ pub fn create_test_buddies(
   test_bed: &TestBed,
 ) -> Result<(), Box<dyn std::error::Error>> {
   create_buddy(test_bed, "pp", "Peter Pan", "peter.pan@example.com")?;
-  create_buddy(
-    test_bed,
-    "wd",
-    "Wendy Darling",
-    "wendy.darling@example.com",
-  )?;
+  create_buddy(test_bed, "wd", "Wendy Darling", "wendy.darling@example.com")?;
   Ok(())
 }
 
@@ -137,3 +190,35 @@ where
 
   Ok(contents)
 }
+
+/// Runs `assertions` with the temporary paths of `test_bed` replaced by
+/// stable placeholders in snapshots.
+pub fn with_path_filters(test_bed: &TestBed, assertions: impl FnOnce()) {
+  let escape = |path: &Path| {
+    path
+      .to_string_lossy()
+      .chars()
+      .map(|c| {
+        if c.is_alphanumeric() {
+          c.to_string()
+        } else {
+          format!("\\{c}")
+        }
+      })
+      .collect::<String>()
+  };
+
+  let mut settings = insta::Settings::clone_current();
+  settings.add_filter(&escape(test_bed.buddies_path()), "[BUDDIES_FILE]");
+  settings.add_filter(&escape(test_bed.path()), "[REPO]");
+  settings.bind(assertions);
+}
+
+/// Runs `assertions` with commit hashes, which change with the git version
+/// and config, replaced by placeholders in snapshots.
+pub fn with_sha_filters(assertions: impl FnOnce()) {
+  let mut settings = insta::Settings::clone_current();
+  settings.add_filter(r"\b[0-9a-f]{40}\b", "[SHA]");
+  settings.add_filter(r"\b[0-9a-f]{7}\b", "[SHORT_SHA]");
+  settings.bind(assertions);
+}
@@ -0,0 +1,199 @@
+use std::{fs, path::Path, process::Command};
+
+use common::{create_test_buddies, make_test_command, setup_git_repo};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+use tempfile::{TempDir, tempdir};
+
+mod common;
+
+/// Creates a workspace with the repositories `api` and `services/web` that
+/// have a commit template and `docs` that doesn't.
+fn setup_workspace() -> Result<TempDir, Box<dyn std::error::Error>> {
+  let workspace = tempdir()?;
+
+  for name in ["api", "services/web", "docs"] {
+    let path = workspace.path().join(name);
+    fs::create_dir_all(&path)?;
+    Command::new("git")
+      .arg("init")
+      .current_dir(&path)
+      .output()?;
+
+    if name != "docs" {
+      fs::write(path.join(".gitmessage"), "Commit template\n")?;
+      Command::new("git")
+        .args(["config", "commit.template", ".gitmessage"])
+        .current_dir(&path)
+        .output()?;
+    }
+  }
+
+  fs::create_dir(workspace.path().join("notes"))?;
+
+  Ok(workspace)
+}
+
+fn workspace_command<const N: usize>(
+  test_bed: &common::TestBed,
+  args: [&str; N],
+) -> Result<Command, Box<dyn std::error::Error>> {
+  let mut cmd = make_test_command(test_bed, args)?;
+  // Make sure no global template is picked up for the docs repository
+  cmd
+    .env("GIT_CONFIG_GLOBAL", "/dev/null")
+    .env("GIT_CONFIG_NOSYSTEM", "1");
+  Ok(cmd)
+}
+
+fn read_template(repo: &Path) -> Result<String, Box<dyn std::error::Error>> {
+  Ok(fs::read_to_string(repo.join(".gitmessage"))?)
+}
+
+#[test]
+fn test_all_repos_with() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  let workspace = setup_workspace()?;
+  let dir = workspace.path().to_string_lossy().to_string();
+
+  let mut cmd =
+    workspace_command(&test_bed, ["with", "pp", "wd", "--all-repos", &dir])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  api:
+  Added buddy 'pp' to the current session
+  Added buddy 'wd' to the current session
+
+  docs:
+
+  services/web:
+  Added buddy 'pp' to the current session
+  Added buddy 'wd' to the current session
+
+  Summary:
+  - api: ok
+  - docs: skipped, no commit template configured
+  - services/web: ok
+
+  ----- stderr -----
+  ");
+
+  assert_snapshot!(read_template(&workspace.path().join("services/web"))?, @"
+  Commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  let mut cmd = workspace_command(&test_bed, ["active", "--all-repos", &dir])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  api:
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  - wd (Wendy Darling <wendy.darling@example.com>)
+
+  docs:
+
+  services/web:
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  - wd (Wendy Darling <wendy.darling@example.com>)
+
+  Summary:
+  - api: ok
+  - docs: skipped, no commit template configured
+  - services/web: ok
+
+  ----- stderr -----
+  ");
+
+  let mut cmd =
+    workspace_command(&test_bed, ["without", "wd", "--all-repos", &dir])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  api:
+  Removed buddy 'wd' from the current session
+
+  docs:
+
+  services/web:
+  Removed buddy 'wd' from the current session
+
+  Summary:
+  - api: ok
+  - docs: skipped, no commit template configured
+  - services/web: ok
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_all_repos_list_file() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  let workspace = setup_workspace()?;
+
+  let list_file = workspace.path().join("repos.txt");
+  fs::write(&list_file, "# Pairing on the api today\napi\n\nmissing\n")?;
+  let list_file = list_file.to_string_lossy().to_string();
+
+  let mut cmd =
+    workspace_command(&test_bed, ["with", "pp", "--all-repos", &list_file])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  api:
+  Added buddy 'pp' to the current session
+
+  missing:
+
+  Summary:
+  - api: ok
+  - missing: failed, directory doesn't exist
+
+  ----- stderr -----
+  Error: Failed in 1 of 2 repositories
+  ");
+
+  let mut cmd =
+    workspace_command(&test_bed, ["alone", "--all-repos", &list_file])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  api:
+  Removed all buddies from the current session
+
+  missing:
+
+  Summary:
+  - api: ok
+  - missing: failed, directory doesn't exist
+
+  ----- stderr -----
+  Error: Failed in 1 of 2 repositories
+  ");
+
+  assert_snapshot!(read_template(&workspace.path().join("api"))?, @"Commit template");
+
+  Ok(())
+}
@@ -0,0 +1,243 @@
+use common::{
+  TestBed, create_test_buddies, git, make_test_command, read_commit_template,
+  setup_git_repo,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+/// Sets up a repository with a `main` branch and installed hooks.
+fn setup_branches() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  git(
+    &test_bed,
+    &["commit", "--allow-empty", "-m", "Initial commit"],
+  )?;
+  git(&test_bed, &["branch", "-M", "main"])?;
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_branch_binding() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_branches()?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+  git(&test_bed, &["switch", "-c", "feature"])?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp", "--branch"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Added buddy 'pp' to the current session
+  Bound the session to branch 'feature'
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session (bound to branch 'feature'):
+  - wd (Wendy Darling <wendy.darling@example.com>)
+  - pp (Peter Pan <peter.pan@example.com>)
+
+  ----- stderr -----
+  ");
+
+  // Back on main the unbound session is restored
+  let output = git(&test_bed, &["switch", "main"])?;
+  assert_snapshot!(output, @"
+  Switched to branch 'main'
+  git-squad: active buddies: wd
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  // And the bound session is activated again on feature
+  let output = git(&test_bed, &["switch", "feature"])?;
+  assert_snapshot!(output, @"
+  Switched to branch 'feature'
+  git-squad: active buddies: wd, pp
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_branch_unbound_switch() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_branches()?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let output = git(&test_bed, &["switch", "-c", "fix"])?;
+  assert_snapshot!(output, @"Switched to a new branch 'fix'");
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_branch_rebase() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_branches()?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+  git(&test_bed, &["switch", "-c", "feature"])?;
+  make_test_command(&test_bed, ["with", "pp", "--branch"])?.output()?;
+
+  git(&test_bed, &["switch", "main"])?;
+  git(&test_bed, &["commit", "--allow-empty", "-m", "Fix the clock"])?;
+  git(&test_bed, &["switch", "feature"])?;
+
+  // Rebasing checks out main detached, which is not the branch left
+  git(&test_bed, &["rebase", "main"])?;
+
+  let output = git(&test_bed, &["switch", "main"])?;
+  assert_snapshot!(output, @"Switched to branch 'main'");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_hook_install() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+
+  let mut cmd = make_test_command(&test_bed, ["hook", "install"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Installed hook '.git/hooks/post-checkout'
+  Installed hook '.git/hooks/post-commit'
+  Installed hook '.git/hooks/prepare-commit-msg'
+  Installed hook '.git/hooks/commit-msg'
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["hook", "uninstall"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Removed hook '.git/hooks/post-checkout'
+  Removed hook '.git/hooks/post-commit'
+  Removed hook '.git/hooks/prepare-commit-msg'
+  Removed hook '.git/hooks/commit-msg'
+
+  ----- stderr -----
+  ");
+
+  std::fs::write(
+    test_bed.path().join(".git/hooks/post-checkout"),
+    "#!/bin/sh\necho custom\n",
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["hook", "install"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Installed hook '.git/hooks/post-commit'
+  Installed hook '.git/hooks/prepare-commit-msg'
+  Installed hook '.git/hooks/commit-msg'
+
+  ----- stderr -----
+  Warning: Skipped hook '.git/hooks/post-checkout', it was not installed by git-squad. Use --force to replace it
+  ");
+
+  let custom =
+    std::fs::read_to_string(test_bed.path().join(".git/hooks/post-checkout"))?;
+  assert_snapshot!(custom, @"
+  #!/bin/sh
+  echo custom
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_hook_install_selected() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["hook", "install", "--hook", "post-checkout", "--hook", "post-commit"],
+  )?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Installed hook '.git/hooks/post-checkout'
+  Installed hook '.git/hooks/post-commit'
+
+  ----- stderr -----
+  ");
+
+  let mut cmd =
+    make_test_command(&test_bed, ["hook", "install", "--hook", "pre-push"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 2
+  ----- stdout -----
+
+  ----- stderr -----
+  error: invalid value 'pre-push' for '--hook <HOOK>'
+    [possible values: post-checkout, post-commit, prepare-commit-msg, commit-msg]
+
+  For more information, try '--help'.
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,104 @@
+use common::{
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+  write_buddies_file,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+#[test]
+fn test_check_buddies() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["check-buddies"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  No problems found.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_check_buddies_problems() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(
+    &test_bed,
+    r#"
+[[buddies]]
+alias = "pp"
+name = "Peter Pan"
+email = "peter.pan@example.com"
+
+[[buddies]]
+alias = "pp"
+name = "Peter <Pan>"
+email = "Peter.Pan@example.com"
+
+[[buddies]]
+alias = "hook"
+name = "Captain Hook"
+email = "hook@example.com>\nCo-authored-by: Smee <smee@example.com"
+"#,
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["check-buddies"])?;
+
+  assert_cmd_snapshot!(cmd, @r"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Problems found in buddies file:
+  - Buddy 'pp': Name 'Peter <Pan>' must not contain '<' or '>'
+  - Duplicate alias 'pp'
+  - Buddies 'pp' and 'pp' share email 'Peter.Pan@example.com'
+  - Buddy 'hook': Email 'hook@example.com>\nCo-authored-by: Smee <smee@example.com' must not contain whitespace or control characters
+
+  ----- stderr -----
+  Error: Found 4 problem(s) in buddies file
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_hand_edited_buddy_not_written() -> Result<(), Box<dyn std::error::Error>>
+{
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(
+    &test_bed,
+    r#"
+[[buddies]]
+alias = "hook"
+name = "Captain Hook"
+email = "hook@example.com>\nCo-authored-by: Smee <smee@example.com"
+"#,
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "hook"])?;
+
+  assert_cmd_snapshot!(cmd, @r"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Added buddy 'hook' to the current session
+
+  ----- stderr -----
+  Error: Refusing to add invalid buddy 'hook' to the commit template, check the buddies file with `git squad check-buddies`
+
+  Caused by:
+      Email 'hook@example.com>\nCo-authored-by: Smee <smee@example.com' must not contain whitespace or control characters
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"Test commit template");
+
+  Ok(())
+}
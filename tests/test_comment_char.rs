@@ -0,0 +1,210 @@
+use std::process::Command;
+
+use common::{
+  TestBed, create_test_buddies, make_test_command, read_commit_template,
+  setup_git_repo, with_path_filters,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+fn git_config(
+  test_bed: &TestBed,
+  key: &str,
+  value: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+  Command::new("git")
+    .args(["config", key, value])
+    .current_dir(test_bed.path())
+    .output()?;
+  Ok(())
+}
+
+/// Commits with the commit template, replacing its first line in the editor,
+/// and returns the message git recorded.
+fn commit_with_template(
+  test_bed: &TestBed,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let output = Command::new("git")
+    .args(["commit", "--allow-empty"])
+    // Drop the instructions of git, which are kept without cleanup
+    .env(
+      "GIT_EDITOR",
+      "sed -i -e '1s/.*/Fix the clock/' -e '/^# Please enter/,$d'",
+    )
+    .current_dir(test_bed.path())
+    .output()?;
+  assert!(output.status.success(), "git commit failed: {output:?}");
+
+  let output = Command::new("git")
+    .args(["log", "-1", "--format=%B"])
+    .current_dir(test_bed.path())
+    .output()?;
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_comment_char() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  git_config(&test_bed, "core.commentChar", ";")?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  ; BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ; END GIT-SQUAD
+  ");
+
+  assert_snapshot!(commit_with_template(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_cleanup_verbatim() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  git_config(&test_bed, "commit.cleanup", "verbatim")?;
+
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ");
+
+  assert_snapshot!(commit_with_template(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp", "--once"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: One-shot buddies can't be marked in a commit template git doesn't strip comments from, check core.commentChar and commit.cleanup
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_comment_char_auto() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  git_config(&test_bed, "core.commentChar", "auto")?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ");
+
+  assert_snapshot!(commit_with_template(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_migrate_section() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  // Sections move to the new format with the next update
+  git_config(&test_bed, "core.commentChar", ";")?;
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  ; BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ; END GIT-SQUAD
+  ");
+
+  git_config(&test_bed, "commit.cleanup", "whitespace")?;
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: 1 problem(s)
+  - git-squad section uses marker comments starting with ';' instead of trailers without markers, check core.commentChar and commit.cleanup (fixable with --fix)
+  Checking buddies file: ok
+  Checking hooks: ok
+
+  ----- stderr -----
+  Error: Found 1 problem(s), 1 can be fixed with --fix
+  ");
+  let mut cmd = make_test_command(&test_bed, ["doctor", "--fix"])?;
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Checking git config: ok
+    Checking commit template: 1 problem(s)
+    - git-squad section uses marker comments starting with ';' instead of trailers without markers, check core.commentChar and commit.cleanup (fixed)
+    Checking buddies file: ok
+    Checking hooks: ok
+    Repaired commit template '[REPO]/commit-template.txt'
+    Fixed all problems.
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ");
+
+  // And back to markers, keeping the active buddies
+  Command::new("git")
+    .args(["config", "--unset", "commit.cleanup"])
+    .current_dir(test_bed.path())
+    .output()?;
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  ; BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  ; END GIT-SQUAD
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,82 @@
+use common::{
+  TestBed, create_buddy, create_test_buddies, make_test_command, setup_git_repo,
+};
+use insta::assert_snapshot;
+
+mod common;
+
+/// Returns the buddy candidates for the last word of `line` like bash asks
+/// for them. Options are filtered out.
+fn complete(
+  test_bed: &TestBed,
+  line: &[&str],
+) -> Result<String, Box<dyn std::error::Error>> {
+  let buddies_path = test_bed.buddies_path().to_string_lossy().to_string();
+
+  let mut args = vec!["--", "git-squad", "--buddies-file", &buddies_path];
+  args.extend(line);
+
+  let output = make_test_command(test_bed, &args)?
+    .env("COMPLETE", "bash")
+    .env("_CLAP_COMPLETE_INDEX", (args.len() - 2).to_string())
+    .output()?;
+
+  Ok(
+    String::from_utf8(output.stdout)?
+      .lines()
+      .filter(|candidate| !candidate.starts_with('-'))
+      .collect::<Vec<_>>()
+      .join("\n"),
+  )
+}
+
+#[test]
+fn test_complete_with() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  create_buddy(&test_bed, "jd", "John Darling", "john.darling@example.com")?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  // Only inactive buddies that are not already on the command line
+  assert_snapshot!(complete(&test_bed, &["with", "jd", ""])?, @"wd");
+
+  Ok(())
+}
+
+#[test]
+fn test_complete_without() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  assert_snapshot!(complete(&test_bed, &["without", ""])?, @"pp");
+
+  Ok(())
+}
+
+#[test]
+fn test_complete_forget() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  assert_snapshot!(complete(&test_bed, &["forget", "w"])?, @"wd");
+
+  Ok(())
+}
+
+#[test]
+fn test_complete_with_self() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  create_buddy(&test_bed, "me", "Test User", "test@example.com")?;
+
+  // You can't be your own co-author
+  assert_snapshot!(complete(&test_bed, &["with", ""])?, @"
+  pp
+  wd
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,159 @@
+use std::{fs, process::Command};
+
+use common::{
+  TestBed, create_test_buddies, git, git_with_env, make_test_command,
+  read_commit_template, setup_git_repo,
+};
+use insta::assert_snapshot;
+
+mod common;
+
+const PP: &str = "Co-authored-by: Peter Pan <peter.pan@example.com>";
+const PP_UPPERCASE: &str = "Co-authored-by: Peter Pan <PETER.PAN@example.com>";
+
+fn last_message(
+  test_bed: &TestBed,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let output = Command::new("git")
+    .args(["log", "-1", "--format=%B"])
+    .current_dir(test_bed.path())
+    .output()?;
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Sets up a repository on `main` with installed hooks and a base commit.
+fn setup_hooks() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  git(&test_bed, &["checkout", "-b", "main"])?;
+  git(&test_bed, &["commit", "--allow-empty", "-m", "Base"])?;
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_dedupe_template() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    format!(
+      "Test commit template\n\n# BEGIN GIT-SQUAD\n{PP}\n{PP_UPPERCASE}\n# END \
+       GIT-SQUAD\n"
+    ),
+  )?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_dedupe_amend() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_hooks()?;
+
+  git(
+    &test_bed,
+    &[
+      "commit",
+      "--allow-empty",
+      "-m",
+      &format!("Fix the clock\n\n{PP}"),
+    ],
+  )?;
+
+  let output = git(
+    &test_bed,
+    &[
+      "commit",
+      "--amend",
+      "--allow-empty",
+      "-m",
+      &format!("Fix the clock\n\n{PP}\n{PP_UPPERCASE}"),
+    ],
+  )?;
+  assert!(output.contains("git-squad: removed duplicate co-author"));
+
+  assert_snapshot!(last_message(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_dedupe_reword() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_hooks()?;
+
+  // Rebases drop empty commits, so this one changes a file
+  fs::write(test_bed.path().join("clock.txt"), "tick tock\n")?;
+  git(&test_bed, &["add", "clock.txt"])?;
+  git(
+    &test_bed,
+    &["commit", "-m", &format!("Fix the clock\n\n{PP}")],
+  )?;
+
+  // Reword the commit, adding the co-author again in the editor
+  let editor = format!("echo '{PP_UPPERCASE}' >>");
+  let output = git_with_env(
+    &test_bed,
+    &["rebase", "-i", "HEAD~1"],
+    &[
+      ("GIT_SEQUENCE_EDITOR", "sed -i -e 's/^pick/reword/'"),
+      ("GIT_EDITOR", &editor),
+    ],
+  )?;
+  assert!(output.contains("git-squad: removed duplicate co-author"));
+  assert_snapshot!(last_message(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_dedupe_cherry_pick() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_hooks()?;
+
+  // A commit made without hooks that already has duplicates
+  git(&test_bed, &["switch", "-c", "feature"])?;
+  git(
+    &test_bed,
+    &[
+      "-c",
+      "core.hooksPath=/dev/null",
+      "commit",
+      "--allow-empty",
+      "-m",
+      &format!("Fix the clock\n\n{PP}\n{PP_UPPERCASE}"),
+    ],
+  )?;
+  git(&test_bed, &["switch", "main"])?;
+
+  let output = git(&test_bed, &["cherry-pick", "--allow-empty", "feature"])?;
+  assert!(output.contains("git-squad: removed duplicate co-author"));
+  assert_snapshot!(last_message(&test_bed)?, @"
+  Fix the clock
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,300 @@
+use std::{fs, process::Command};
+
+use common::{
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+  with_path_filters,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+const PP: &str = "Co-authored-by: Peter Pan <peter.pan@example.com>";
+const WD: &str = "Co-authored-by: Wendy Darling <wendy.darling@example.com>";
+
+#[test]
+fn test_doctor() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: ok
+  Checking buddies file: ok
+  Checking hooks: ok
+  No problems found.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_unclosed_section() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    format!("Test commit template\n\n# BEGIN GIT-SQUAD\n{PP}\n\nKeep me\n"),
+  )?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: 1 problem(s)
+  - line 3: git-squad section is never closed (fixable with --fix)
+  Checking buddies file: ok
+  Checking hooks: ok
+
+  ----- stderr -----
+  Error: Found 1 problem(s), 1 can be fixed with --fix
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["doctor", "--fix"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Checking git config: ok
+    Checking commit template: 1 problem(s)
+    - line 3: git-squad section is never closed (fixed)
+    Checking buddies file: ok
+    Checking hooks: ok
+    Repaired commit template '[REPO]/commit-template.txt'
+    Fixed all problems.
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  Keep me
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_duplicate_sections() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    format!(
+      "Test commit template\r\n\r\n  # BEGIN GIT-SQUAD\r\n{PP}\r\n# END \
+       GIT-SQUAD\r\n# BEGIN GIT-SQUAD\r\n{WD}\r\n# END GIT-SQUAD\r\n"
+    ),
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["doctor", "--fix"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Checking git config: ok
+    Checking commit template: 2 problem(s)
+    - line 3: indented git-squad marker (fixed)
+    - line 6: duplicate git-squad section (fixed)
+    Checking buddies file: ok
+    Checking hooks: ok
+    Repaired commit template '[REPO]/commit-template.txt'
+    Fixed all problems.
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_eq!(
+    commit_template,
+    format!(
+      "Test commit template\r\n\r\n# BEGIN GIT-SQUAD\r\n{PP}\r\n{WD}\r\n# END \
+       GIT-SQUAD\r\n"
+    )
+  );
+
+  // Updates keep the Windows line endings
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_eq!(
+    commit_template,
+    format!(
+      "Test commit template\r\n\r\n# BEGIN GIT-SQUAD\r\n{WD}\r\n# END GIT-SQUAD\r\n"
+    )
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_hooks() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  // A hook installed by an executable that was moved since
+  fs::write(
+    test_bed.path().join(".git/hooks/post-commit"),
+    "#!/bin/sh\n# Installed by git-squad, remove with `git squad hook \
+     uninstall`\nexec '/opt/git-squad' hook run post-commit \"$@\"\n",
+  )?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: ok
+  Checking buddies file: ok
+  Checking hooks: 1 problem(s)
+  - Hook 'post-commit' runs another git-squad executable or buddies file (fixable with --fix)
+
+  ----- stderr -----
+  Error: Found 1 problem(s), 1 can be fixed with --fix
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["doctor", "--fix"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Checking git config: ok
+    Checking commit template: ok
+    Checking buddies file: ok
+    Checking hooks: 1 problem(s)
+    - Hook 'post-commit' runs another git-squad executable or buddies file (fixed)
+    Installed hook '.git/hooks/post-commit'
+    Fixed all problems.
+
+    ----- stderr -----
+    ");
+  });
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: ok
+  Checking buddies file: ok
+  Checking hooks: ok
+  No problems found.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_no_template() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["config", "--unset", "commit.template"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor", "--fix"])?, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: 1 problem(s)
+  - No commit template configured, set one with `git config commit.template <file>`
+  Checking buddies file: ok
+  Checking hooks: ok
+
+  ----- stderr -----
+  Error: Found 1 problem(s)
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_config() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["config", "squad.strict", "maybe"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Checking git config: 1 problem(s)
+  - Failed to run `git config --type=bool --get squad.strict`: fatal: bad boolean config value 'maybe' for 'squad.strict'
+  Checking commit template: ok
+  Checking buddies file: ok
+  Checking hooks: ok
+
+  ----- stderr -----
+  Error: Found 1 problem(s)
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_doctor_relative_template() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  // The template is created in the directory git runs in on first use
+  Command::new("git")
+    .args(["config", "commit.template", "missing-template.txt"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["doctor"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Checking git config: ok
+  Checking commit template: ok
+  Checking buddies file: ok
+  Checking hooks: ok
+  No problems found.
+
+  ----- stderr -----
+  ");
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+  let commit_template =
+    fs::read_to_string(test_bed.path().join("missing-template.txt"))?;
+  assert_snapshot!(commit_template, @"
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,160 @@
+use common::{
+  create_test_buddies, make_test_command, read_buddies_file,
+  read_commit_template, setup_git_repo, spawn_test_command, with_path_filters,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+#[test]
+fn test_dry_run_with() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["--dry-run", "with", "pp"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Added buddy 'pp' to the current session
+    --- [REPO]/commit-template.txt
+    +++ [REPO]/commit-template.txt
+    @@ -1 +1,5 @@
+     Test commit template
+    +
+    +# BEGIN GIT-SQUAD
+    +Co-authored-by: Peter Pan <peter.pan@example.com>
+    +# END GIT-SQUAD
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"Test commit template");
+
+  Ok(())
+}
+
+#[test]
+fn test_dry_run_alone() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["alone", "--dry-run"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    --- [REPO]/commit-template.txt
+    +++ [REPO]/commit-template.txt
+    @@ -1,6 +1 @@
+    -Test commit template
+    -
+    -# BEGIN GIT-SQUAD
+    -Co-authored-by: Peter Pan <peter.pan@example.com>
+    -Co-authored-by: Wendy Darling <wendy.darling@example.com>
+    -# END GIT-SQUAD
+    +Test commit template
+    \ No newline at end of file
+    Removed all buddies from the current session
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_dry_run_forget() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["forget", "wd", "--dry-run"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @r#"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    --- [REPO]/commit-template.txt
+    +++ [REPO]/commit-template.txt
+    @@ -1,5 +1 @@
+    -Test commit template
+    -
+    -# BEGIN GIT-SQUAD
+    -Co-authored-by: Wendy Darling <wendy.darling@example.com>
+    -# END GIT-SQUAD
+    +Test commit template
+    \ No newline at end of file
+    --- [BUDDIES_FILE]
+    +++ [BUDDIES_FILE]
+    @@ -2,8 +2,3 @@
+     alias = "pp"
+     name = "Peter Pan"
+     email = "peter.pan@example.com"
+    -
+    -[[buddies]]
+    -alias = "wd"
+    -name = "Wendy Darling"
+    -email = "wendy.darling@example.com"
+    Completly forgot buddy 'wd'
+
+    ----- stderr -----
+    "#);
+  });
+
+  let buddies = read_buddies_file(&test_bed)?;
+  assert_snapshot!(buddies, @r#"
+  [[buddies]]
+  alias = "pp"
+  name = "Peter Pan"
+  email = "peter.pan@example.com"
+
+  [[buddies]]
+  alias = "wd"
+  name = "Wendy Darling"
+  email = "wendy.darling@example.com"
+  "#);
+
+  Ok(())
+}
+
+#[test]
+fn test_dry_run_create() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  let mut proc =
+    spawn_test_command(&test_bed, ["create", "peter", "--dry-run"])?;
+
+  proc.exp_string("Enter name")?;
+  proc.send_line("Peter Pan")?;
+  proc.exp_string("Enter email")?;
+  proc.send_line("peter.pan@example.com")?;
+  proc.exp_string("+alias = \"peter\"")?;
+  proc.exp_eof()?;
+
+  let buddies = read_buddies_file(&test_bed)?;
+  assert_snapshot!(buddies, @"");
+
+  Ok(())
+}
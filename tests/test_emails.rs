@@ -0,0 +1,92 @@
+use std::process::Command;
+
+use common::{
+  make_test_command, read_commit_template, setup_git_repo, write_buddies_file,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+const BUDDIES: &str = r#"
+[[buddies]]
+alias = "pp"
+name = "Peter Pan"
+email = "peter@neverland.org"
+
+[[buddies.emails]]
+email = "peter.pan@corp.example.com"
+remote = "git@corp.example.com:*"
+"#;
+
+#[test]
+fn test_with_selects_email_by_remote() -> Result<(), Box<dyn std::error::Error>>
+{
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(&test_bed, BUDDIES)?;
+
+  Command::new("git")
+    .args([
+      "remote",
+      "add",
+      "origin",
+      "git@corp.example.com:team/app.git",
+    ])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+  cmd.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @r"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@corp.example.com>
+  # END GIT-SQUAD
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@corp.example.com>)
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_uses_default_email() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(&test_bed, BUDDIES)?;
+
+  Command::new("git")
+    .args([
+      "remote",
+      "add",
+      "origin",
+      "https://github.com/neverland/app",
+    ])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+  cmd.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @r"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter@neverland.org>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
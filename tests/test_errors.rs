@@ -0,0 +1,172 @@
+use std::{fs, process::Command};
+
+use common::{
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+  with_path_filters, write_buddies_file,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+#[test]
+fn test_error_config_parse() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(
+    &test_bed,
+    r#"[[buddies]]
+alias = "pp"
+name = "Peter Pan
+"#,
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["list"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: false
+    exit_code: 10
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: Failed to parse buddies file [BUDDIES_FILE]:3:18: invalid basic string
+    ");
+  });
+
+  Ok(())
+}
+
+#[test]
+fn test_error_template_parse() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let template = "Test commit template\n\n# BEGIN GIT-SQUAD\n\nKeep me\n";
+  fs::write(test_bed.path().join("commit-template.txt"), template)?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: false
+    exit_code: 9
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: Failed to parse commit template [REPO]/commit-template.txt:3: git-squad section is never closed, run `git squad doctor --fix` to repair it
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+
+  Keep me
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_error_duplicate_alias() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["create", "pp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 8
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy with alias 'pp' already exists
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_error_git_failure() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+  cmd.env("PATH", "");
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 11
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Failed to run `git config --type=bool --get squad.noreply`: No such file or directory (os error 2)
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_error_invalid_config_bool() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["config", "squad.strict", "maybe"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 11
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Failed to run `git config --type=bool --get squad.strict`: fatal: bad boolean config value 'maybe' for 'squad.strict'
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"Test commit template");
+
+  Ok(())
+}
+
+#[test]
+fn test_error_format_json() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["with", "--strict", "--error-format", "json", "hook"],
+  )?;
+
+  assert_cmd_snapshot!(cmd, @r#"
+  success: false
+  exit_code: 3
+  ----- stdout -----
+
+  ----- stderr -----
+  {"code":"not_found","exit_code":3,"message":"Buddy with alias 'hook' does not exist","query":"hook","suggestions":[]}
+  "#);
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["without", "--strict", "--error-format", "json", "wd"],
+  )?;
+
+  assert_cmd_snapshot!(cmd, @r#"
+  success: false
+  exit_code: 6
+  ----- stdout -----
+
+  ----- stderr -----
+  {"alias":"wd","code":"not_active","exit_code":6,"message":"Buddy 'wd' is not active"}
+  "#);
+
+  Ok(())
+}
@@ -1,4 +1,4 @@
-use common::{create_test_buddies, make_test_command, read_buddies_file, setup_git_repo};
+use common::{create_test_buddies, make_test_command, read_buddies_file, setup_git_repo, spawn_test_command};
 use insta_cmd::assert_cmd_snapshot;
 
 mod common;
@@ -37,12 +37,96 @@ fn test_forget_non_existing() -> Result<(), Box<dyn std::error::Error>> {
   let mut cmd = make_test_command(&test_bed, ["forget", "pp"])?;
   assert_cmd_snapshot!(cmd, @r"
   success: false
-  exit_code: 1
+  exit_code: 3
   ----- stdout -----
 
   ----- stderr -----
-  Error: Buddy with alias 'pp' doesn't exist
+  Error: Buddy with alias 'pp' does not exist
   ");
 
   Ok(())
 }
+
+#[test]
+fn test_forget_fuzzy_without_terminal()
+-> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  // Nobody can confirm the match, so it is only suggested
+  let mut cmd = make_test_command(&test_bed, ["forget", "ppp"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 3
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy with alias 'ppp' does not exist
+  Did you mean:
+    pp (Peter Pan <peter.pan@example.com>)
+  ");
+
+  let buddies = read_buddies_file(&test_bed)?;
+  insta::assert_snapshot!(buddies, @r#"
+  [[buddies]]
+  alias = "pp"
+  name = "Peter Pan"
+  email = "peter.pan@example.com"
+
+  [[buddies]]
+  alias = "wd"
+  name = "Wendy Darling"
+  email = "wendy.darling@example.com"
+  "#);
+
+  Ok(())
+}
+
+#[test]
+fn test_forget_fuzzy_confirmed() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut proc = spawn_test_command(&test_bed, ["forget", "ppp"])?;
+  proc.exp_string("Forget buddy 'pp' (Peter Pan <peter.pan@example.com>)?")?;
+  proc.send_line("y")?;
+  proc.exp_string("Completly forgot buddy 'pp'")?;
+  proc.exp_eof()?;
+
+  let buddies = read_buddies_file(&test_bed)?;
+  insta::assert_snapshot!(buddies, @r#"
+  [[buddies]]
+  alias = "wd"
+  name = "Wendy Darling"
+  email = "wendy.darling@example.com"
+  "#);
+
+  Ok(())
+}
+
+#[test]
+fn test_forget_fuzzy_declined() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut proc = spawn_test_command(&test_bed, ["forget", "wendy"])?;
+  proc.exp_string("Forget buddy 'wd'")?;
+  proc.send_line("n")?;
+  proc.exp_string("Kept all buddies")?;
+  proc.exp_eof()?;
+
+  let buddies = read_buddies_file(&test_bed)?;
+  insta::assert_snapshot!(buddies, @r#"
+  [[buddies]]
+  alias = "pp"
+  name = "Peter Pan"
+  email = "peter.pan@example.com"
+
+  [[buddies]]
+  alias = "wd"
+  name = "Wendy Darling"
+  email = "wendy.darling@example.com"
+  "#);
+
+  Ok(())
+}
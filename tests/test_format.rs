@@ -0,0 +1,79 @@
+use common::{create_test_buddies, make_test_command, setup_git_repo};
+use insta_cmd::assert_cmd_snapshot;
+use test_case::test_case;
+
+mod common;
+
+#[test_case("json")]
+#[test_case("yaml")]
+#[test_case("tsv")]
+#[test_case("porcelain")]
+fn test_list_format(format: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["--format", format, "list"])?;
+
+  insta::with_settings!({snapshot_suffix => format}, {
+    assert_cmd_snapshot!(cmd);
+  });
+
+  Ok(())
+}
+
+#[test_case("json")]
+#[test_case("yaml")]
+#[test_case("tsv")]
+#[test_case("porcelain")]
+fn test_active_format(format: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["--format", format, "active"])?;
+
+  insta::with_settings!({snapshot_suffix => format}, {
+    assert_cmd_snapshot!(cmd);
+  });
+
+  Ok(())
+}
+
+#[test_case("json")]
+#[test_case("yaml")]
+#[test_case("tsv")]
+#[test_case("porcelain")]
+fn test_info_format(format: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["--format", format, "info"])?;
+
+  insta::with_settings!({snapshot_suffix => format}, {
+    assert_cmd_snapshot!(cmd);
+  });
+
+  Ok(())
+}
+
+#[test]
+fn test_active_porcelain_empty() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+
+  let mut cmd = make_test_command(&test_bed, ["--porcelain", "active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
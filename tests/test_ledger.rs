@@ -0,0 +1,171 @@
+use std::process::Command;
+
+use common::{
+  TestBed, commit, create_test_buddies, git_with_env, make_test_command,
+  setup_git_repo, with_sha_filters,
+};
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+/// Sets up a repository with installed hooks on the `main` branch.
+fn setup_ledger() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["checkout", "-b", "main"])
+    .current_dir(test_bed.path())
+    .output()?;
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_ledger() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_ledger()?;
+
+  commit(&test_bed, "Commit")?;
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+  commit(&test_bed, "Commit")?;
+  commit(&test_bed, "Commit")?;
+
+  let mut cmd = make_test_command(&test_bed, ["ledger"])?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Session without buddies (1 commit):
+    - [SHORT_SHA] 2025-01-01T12:00:00+01:00 on main
+
+    Session with pp, wd (2 commits):
+    - [SHORT_SHA] 2025-01-01T12:00:00+01:00 on main
+    - [SHORT_SHA] 2025-01-01T12:00:00+01:00 on main
+
+    ----- stderr -----
+    ");
+  });
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  - wd (Wendy Darling <wendy.darling@example.com>)
+  2 commits made in the current session.
+
+  ----- stderr -----
+  ");
+
+  make_test_command(&test_bed, ["without", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  0 commits made in the current session.
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["ledger", "--porcelain"])?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    [SHA] 2025-01-01T12:00:00+01:00 main -
+    [SHA] 2025-01-01T12:00:00+01:00 main pp,wd
+    [SHA] 2025-01-01T12:00:00+01:00 main pp,wd
+
+    ----- stderr -----
+    ");
+  });
+
+  Ok(())
+}
+
+#[test]
+fn test_ledger_session_start() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_ledger()?;
+
+  make_test_command(&test_bed, ["with", "pp", "--once"])?.output()?;
+  git_with_env(
+    &test_bed,
+    &["commit", "--allow-empty"],
+    &[("GIT_EDITOR", "sed -i -e '1s/.*/Commit/'")],
+  )?;
+
+  // The commit with the one-shot buddy was made in another session
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  0 commits made in the current session.
+
+  ----- stderr -----
+  ");
+
+  commit(&test_bed, "Commit")?;
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  1 commit made in the current session.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_ledger_empty() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+
+  let mut cmd = make_test_command(&test_bed, ["ledger"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  No commits recorded yet. Run `git squad hook install` to record commits.
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["ledger", "--format", "json"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  []
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
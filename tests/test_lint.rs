@@ -0,0 +1,152 @@
+use std::{fs, process::Command};
+
+use common::{TestBed, create_test_buddies, make_test_command, setup_git_repo};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+const POLICY: &str = r#"allowed_domains = ["example.com"]
+min_co_authors = 1
+max_co_authors = 2
+"#;
+
+fn setup_policy() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  fs::write(test_bed.path().join(".git-squad-policy.toml"), POLICY)?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_lint_message() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_policy()?;
+  let file = test_bed.path().join("message.txt");
+
+  fs::write(
+    &file,
+    "Fix the clock\n\n\
+     Co-authored-by: Peter Pan <peter.pan@example.com>\n\
+     Co-authored-by: Captain Hook <hook@jolly-roger.sea>\n",
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["lint-message", "message.txt"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Problems found in commit message:
+  - Co-author 'hook@jolly-roger.sea' is not in an allowed domain (example.com)
+  - Unknown co-author 'hook@jolly-roger.sea'
+
+  ----- stderr -----
+  Error: Found 2 problem(s) in commit message
+  ");
+
+  fs::write(
+    &file,
+    "Fix the clock\n\n\
+     Co-authored-by: Peter Pan <peter.pan@example.com>\n",
+  )?;
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["lint-message", "message.txt", "--format", "json"],
+  )?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  []
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_lint_message_self_mailmap() -> Result<(), Box<dyn std::error::Error>>
+{
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  let file = test_bed.path().join("message.txt");
+
+  fs::write(
+    test_bed.path().join(".mailmap"),
+    "Test User <test@example.com> <test.user@old.example.com>\n",
+  )?;
+  fs::write(
+    &file,
+    "Fix the clock\n\n\
+     Co-authored-by: Test User <test.user@old.example.com>\n",
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["lint-message", "message.txt"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 1
+  ----- stdout -----
+  Problems found in commit message:
+  - Co-author 'test.user@old.example.com' is the committer
+  - Unknown co-author 'test.user@old.example.com'
+
+  ----- stderr -----
+  Error: Found 2 problem(s) in commit message
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_commit_msg_hook() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_policy()?;
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  let commit = |message: &str| -> Result<_, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+      .args(["commit", "--allow-empty", "-m", message])
+      .current_dir(test_bed.path())
+      .env_remove("RUST_BACKTRACE")
+      .env_remove("RUST_LIB_BACKTRACE")
+      .output()?;
+    Ok((output.status.success(), String::from_utf8(output.stderr)?))
+  };
+
+  let (success, output) = commit("Solo commit")?;
+  assert!(!success);
+  assert_snapshot!(output, @"
+  git-squad: the commit message violates the co-author policy:
+  - Expected at least 1 co-author(s), found 0
+  Error: Commit rejected, fix the co-authors and try again
+  ");
+
+  let (success, output) = commit(
+    "Pair commit\n\nCo-authored-by: Wendy Darling \
+     <wendy.darling@example.com>",
+  )?;
+  assert!(success);
+  assert_snapshot!(output, @"");
+
+  Ok(())
+}
+
+#[test]
+fn test_commit_msg_hook_without_policy()
+-> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  make_test_command(&test_bed, ["hook", "install"])?.output()?;
+
+  let output = Command::new("git")
+    .args(["commit", "--allow-empty", "-m", "Solo commit"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  assert!(output.status.success());
+
+  Ok(())
+}
@@ -0,0 +1,122 @@
+use std::process::Command;
+
+use common::{
+  make_test_command, read_buddies_file, read_commit_template, setup_git_repo,
+  spawn_test_command, write_buddies_file,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+const BUDDIES: &str = r#"
+[[buddies]]
+alias = "pp"
+name = "Peter Pan"
+email = "peter@neverland.org"
+
+[buddies.github]
+login = "peterpan"
+id = 1904
+
+[[buddies]]
+alias = "wd"
+name = "Wendy Darling"
+email = "wendy.darling@example.com"
+"#;
+
+#[test]
+fn test_with_noreply() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(&test_bed, BUDDIES)?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["--noreply", "with", "pp", "wd"])?;
+  cmd.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <1904+peterpan@users.noreply.github.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter@neverland.org>)
+  - wd (Wendy Darling <wendy.darling@example.com>)
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_noreply_repo_setting() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  write_buddies_file(&test_bed, BUDDIES)?;
+
+  Command::new("git")
+    .args(["config", "squad.noreply", "true"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+  cmd.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <1904+peterpan@users.noreply.github.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_create_with_github() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  let mut proc = spawn_test_command(
+    &test_bed,
+    [
+      "create",
+      "peter",
+      "--github",
+      "peterpan",
+      "--github-id",
+      "1904",
+    ],
+  )?;
+
+  proc.exp_string("Enter name")?;
+  proc.send_line("Peter Pan")?;
+  proc.exp_string("Enter email")?;
+  proc.send_line("peter@neverland.org")?;
+  proc.exp_string("Created new buddy")?;
+  proc.exp_eof()?;
+
+  let buddies = read_buddies_file(&test_bed)?;
+  insta::assert_snapshot!(buddies, @r#"
+  [[buddies]]
+  alias = "peter"
+  name = "Peter Pan"
+  email = "peter@neverland.org"
+
+  [buddies.github]
+  login = "peterpan"
+  id = 1904
+  "#);
+
+  Ok(())
+}
@@ -0,0 +1,173 @@
+use common::{
+  TestBed, create_test_buddies, git, git_with_env, make_test_command,
+  read_commit_template, setup_git_repo,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+/// Commits with the commit template, replacing its first line in the editor,
+/// and returns the output of the hooks.
+fn commit_with_template(
+  test_bed: &TestBed,
+) -> Result<String, Box<dyn std::error::Error>> {
+  git_with_env(
+    test_bed,
+    &["commit", "--allow-empty"],
+    &[("GIT_EDITOR", "sed -i -e '1s/.*/Commit/'")],
+  )
+}
+
+#[test]
+fn test_once() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp", "--once"])?;
+
+  common::with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Added buddy 'pp' for the next commit
+    Installed hook '.git/hooks/post-commit'
+
+    ----- stderr -----
+    ");
+  });
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # ONCE: pp
+  # END GIT-SQUAD
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - wd (Wendy Darling <wendy.darling@example.com>)
+  - pp (Peter Pan <peter.pan@example.com>) (next commit only)
+
+  ----- stderr -----
+  ");
+
+  // Adding another buddy keeps the one-shot marker
+  common::create_buddy(
+    &test_bed,
+    "jh",
+    "James Hook",
+    "james.hook@example.com",
+  )?;
+  make_test_command(&test_bed, ["with", "jh"])?.output()?;
+
+  let output = commit_with_template(&test_bed)?;
+  assert_snapshot!(output, @"git-squad: removed one-shot buddies: pp");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  Co-authored-by: James Hook <james.hook@example.com>
+  # END GIT-SQUAD
+  ");
+
+  // The next commit leaves the session alone
+  let output = git(&test_bed, &["commit", "--allow-empty", "-m", "Commit"])?;
+  assert_snapshot!(output, @"");
+
+  Ok(())
+}
+
+#[test]
+fn test_once_message() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp", "--once"])?.output()?;
+
+  // A message given on the command line doesn't credit the buddy
+  let output = git(&test_bed, &["commit", "--allow-empty", "-m", "quick fix"])?;
+  assert_snapshot!(output, @"");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # ONCE: pp
+  # END GIT-SQUAD
+  ");
+
+  let output = commit_with_template(&test_bed)?;
+  assert_snapshot!(output, @"git-squad: removed one-shot buddies: pp");
+
+  Ok(())
+}
+
+#[test]
+fn test_once_removed_early() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp", "--once"])?.output()?;
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  // Adding the buddy again makes it part of the session
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let output = git(&test_bed, &["commit", "--allow-empty", "-m", "Commit"])?;
+  assert_snapshot!(output, @"");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_once_foreign_hook() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let hooks_dir = test_bed.path().join(".git/hooks");
+  std::fs::create_dir_all(&hooks_dir)?;
+  std::fs::write(hooks_dir.join("post-commit"), "#!/bin/sh\n")?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp", "--once"])?;
+
+  common::with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Added buddy 'pp' for the next commit
+
+    ----- stderr -----
+    Warning: Hook '.git/hooks/post-commit' was not installed by git-squad, use --force to replace it. One-shot buddies won't be removed after the next commit
+    ");
+  });
+
+  Ok(())
+}
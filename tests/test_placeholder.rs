@@ -0,0 +1,193 @@
+use std::{fs, process::Command};
+
+use common::{
+  TestBed, create_test_buddies, make_test_command, read_commit_template,
+  setup_git_repo,
+};
+use insta::assert_snapshot;
+
+mod common;
+
+const TEMPLATE: &str = "[TICKET-]\n\n# {{git-squad}}\n# Explain why, not \
+                        what\n# Link the design doc\n";
+
+/// Commits with the commit template, replacing its first line in the editor,
+/// and returns the message git recorded.
+fn commit_with_template(
+  test_bed: &TestBed,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let output = Command::new("git")
+    .args(["commit", "--allow-empty"])
+    .env("GIT_EDITOR", "sed -i -e '1s/.*/[TICKET-42] Fix the clock/'")
+    .current_dir(test_bed.path())
+    .output()?;
+  assert!(output.status.success(), "git commit failed: {output:?}");
+
+  let output = Command::new("git")
+    .args(["log", "-1", "--format=%B"])
+    .current_dir(test_bed.path())
+    .output()?;
+  Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn test_placeholder() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  fs::write(test_bed.path().join("commit-template.txt"), TEMPLATE)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  # Explain why, not what
+  # Link the design doc
+  ");
+
+  assert_snapshot!(commit_with_template(&test_bed)?, @"
+  [TICKET-42] Fix the clock
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  // The empty section keeps its place for the next session
+  make_test_command(&test_bed, ["alone"])?.output()?;
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # BEGIN GIT-SQUAD
+  # END GIT-SQUAD
+  # Explain why, not what
+  # Link the design doc
+  ");
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  # Explain why, not what
+  # Link the design doc
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_placeholder_before_session() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  fs::write(test_bed.path().join("commit-template.txt"), TEMPLATE)?;
+
+  // The placeholder is a comment until git-squad replaces it
+  assert_snapshot!(commit_with_template(&test_bed)?, @"[TICKET-42] Fix the clock");
+
+  Ok(())
+}
+
+#[test]
+fn test_placeholder_trailers() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  Command::new("git")
+    .args(["config", "commit.cleanup", "verbatim"])
+    .current_dir(test_bed.path())
+    .output()?;
+  fs::write(test_bed.path().join("commit-template.txt"), TEMPLATE)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  // Trailers go to the end, the placeholder stays for markers
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # {{git-squad}}
+  # Explain why, not what
+  # Link the design doc
+
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  ");
+
+  Command::new("git")
+    .args(["config", "--unset", "commit.cleanup"])
+    .current_dir(test_bed.path())
+    .output()?;
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  # Explain why, not what
+  # Link the design doc
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_placeholder_configured() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  Command::new("git")
+    .args(["config", "squad.placeholder", "<co-authors>"])
+    .current_dir(test_bed.path())
+    .output()?;
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    TEMPLATE.replace("{{git-squad}}", "<co-authors>"),
+  )?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  # Explain why, not what
+  # Link the design doc
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_placeholder_fallback() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    "[TICKET-]\n\n# Explain why, not what\n",
+  )?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  [TICKET-]
+
+  # Explain why, not what
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,111 @@
+use common::{
+  create_test_buddies, make_test_command, setup_git_repo, write_buddies_file,
+};
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+#[test]
+fn test_prompt_no_session() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["prompt", "👥 {aliases}"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_prompt() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["prompt", "👥 {aliases} ({count})"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  👥 pp, wd (2)
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_prompt_cache() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+  make_test_command(&test_bed, ["prompt", "{names}"])?.output()?;
+
+  // A cache hit must not need git at all
+  let mut cmd = make_test_command(&test_bed, ["prompt", "{names}"])?;
+  cmd.env("PATH", "");
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Peter Pan
+  ----- stderr -----
+  ");
+
+  // Changing the session invalidates the cache
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+  let mut cmd = make_test_command(&test_bed, ["prompt", "{names}"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Peter Pan, Wendy Darling
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_prompt_cache_buddies_file() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+  make_test_command(&test_bed, ["prompt", "{aliases}"])?.output()?;
+
+  // Another buddies file knows Peter Pan by another alias
+  let other = setup_git_repo()?;
+  write_buddies_file(
+    &other,
+    r#"
+[[buddies]]
+alias = "op"
+name = "Peter Pan"
+email = "peter.pan@example.com"
+"#,
+  )?;
+
+  let mut cmd = make_test_command(&other, ["prompt", "{aliases}"])?;
+  cmd.current_dir(test_bed.path());
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  op
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
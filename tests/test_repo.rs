@@ -0,0 +1,61 @@
+use std::process::Command;
+
+use common::{
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+use tempfile::tempdir;
+
+mod common;
+
+#[test]
+fn test_repo_from_outside() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  // A relative template path is resolved inside the repository
+  Command::new("git")
+    .args(["config", "commit.template", "commit-template.txt"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let outside = tempdir()?;
+  let repo = test_bed.path().to_string_lossy().to_string();
+
+  let mut cmd = make_test_command(&test_bed, ["-C", &repo, "with", "pp"])?;
+  cmd.current_dir(outside.path());
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Added buddy 'pp' to the current session
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["active", "--repo", &repo])?;
+  cmd.current_dir(outside.path());
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+
+  ----- stderr -----
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,137 @@
+use std::{fs, path::Path, process::Command};
+
+use common::{
+  TestBed, create_test_buddies, make_test_command, read_commit_template,
+  setup_git_repo, with_path_filters,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+use tempfile::{TempDir, tempdir};
+
+mod common;
+
+fn git(dir: &Path, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+  let output = Command::new("git")
+    .args(["-c", "protocol.file.allow=always"])
+    .args(args)
+    .current_dir(dir)
+    .output()?;
+  assert!(output.status.success(), "git {args:?} failed: {output:?}");
+  Ok(())
+}
+
+/// Adds the submodules `lib` without and `vendor` with an own commit
+/// template to the test repository.
+fn add_submodules(
+  test_bed: &TestBed,
+) -> Result<TempDir, Box<dyn std::error::Error>> {
+  let upstream = tempdir()?;
+  git(upstream.path(), &["init"])?;
+  git(
+    upstream.path(),
+    &[
+      "-c",
+      "user.name=Test",
+      "-c",
+      "user.email=test@example.com",
+      "commit",
+      "--allow-empty",
+      "-m",
+      "Initial commit",
+    ],
+  )?;
+
+  let upstream_path = upstream.path().to_string_lossy().to_string();
+  for name in ["lib", "vendor"] {
+    git(test_bed.path(), &["submodule", "add", &upstream_path, name])?;
+  }
+
+  let vendor = test_bed.path().join("vendor");
+  fs::write(vendor.join(".gitmessage"), "Vendor template\n")?;
+  git(&vendor, &["config", "commit.template", ".gitmessage"])?;
+
+  Ok(upstream)
+}
+
+#[test]
+fn test_with_recurse_submodules() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  let _upstream = add_submodules(&test_bed)?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["with", "pp", "--recurse-submodules"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Added buddy 'pp' to the current session
+    Configured submodule '[REPO]/lib' to use '[REPO]/commit-template.txt'
+    Updated submodule '[REPO]/vendor'
+
+    ----- stderr -----
+    ");
+  });
+
+  let vendor_template =
+    fs::read_to_string(test_bed.path().join("vendor/.gitmessage"))?;
+  assert_snapshot!(vendor_template, @"
+  Vendor template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  // The submodule without a template shares the one of the parent
+  let mut cmd = make_test_command(&test_bed, ["active"])?;
+  cmd.current_dir(test_bed.path().join("lib"));
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_alone_recurse_submodules_config()
+-> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  let _upstream = add_submodules(&test_bed)?;
+
+  git(test_bed.path(), &["config", "squad.recurseSubmodules", "true"])?;
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["alone"])?;
+
+  with_path_filters(&test_bed, || {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Updated submodule '[REPO]/vendor'
+    Removed all buddies from the current session
+
+    ----- stderr -----
+    ");
+  });
+
+  let vendor_template =
+    fs::read_to_string(test_bed.path().join("vendor/.gitmessage"))?;
+  assert_snapshot!(vendor_template, @"Vendor template");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"Test commit template");
+
+  Ok(())
+}
@@ -0,0 +1,110 @@
+use std::{fs, process::Command};
+
+use common::{
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+#[test]
+fn test_trailer_config() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["config", "trailer.co.key", "Co-authored-by"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  // An abbreviated token, and a lowercase one folded over two lines
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    "Test commit template\n\nco: Peter Pan <peter.pan@example.com>\n\
+     co-authored-by : Wendy Darling\n  <wendy.darling@example.com>\n",
+  )?;
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["active"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  - wd (Wendy Darling <wendy.darling@example.com>)
+
+  ----- stderr -----
+  ");
+
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_trailer_config_rendering() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  for (key, value) in [
+    ("trailer.separators", "="),
+    ("trailer.co.key", "Co-Authored-By"),
+  ] {
+    Command::new("git")
+      .args(["config", key, value])
+      .current_dir(test_bed.path())
+      .output()?;
+  }
+
+  make_test_command(&test_bed, ["with", "pp", "wd"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-Authored-By= Peter Pan <peter.pan@example.com>
+  Co-Authored-By= Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  // The written trailers are read back
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-Authored-By= Wendy Darling <wendy.darling@example.com>
+  # END GIT-SQUAD
+  ");
+
+  fs::write(
+    test_bed.path().join("message.txt"),
+    "Fix the clock\n\n\
+     Co-Authored-By= Wendy Darling <wendy.darling@example.com>\n",
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["lint-message", "message.txt"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  No problems found.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
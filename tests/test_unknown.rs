@@ -0,0 +1,219 @@
+use std::fs;
+
+use common::{
+  TestBed, create_test_buddies, make_test_command, read_buddies_file,
+  read_commit_template, setup_git_repo,
+};
+use insta::assert_snapshot;
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+const HOOK: &str = "Co-authored-by: Captain Hook <hook@jolly-roger.sea>";
+
+/// Sets up a session with Peter Pan and a co-author added by hand.
+fn setup_unknown() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  fs::write(
+    test_bed.path().join("commit-template.txt"),
+    format!(
+      "Test commit template\n\n# BEGIN GIT-SQUAD\nCo-authored-by: Peter Pan \
+       <peter.pan@example.com>\n{HOOK}\n# END GIT-SQUAD\n"
+    ),
+  )?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_unknown_kept() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_unknown()?;
+
+  make_test_command(&test_bed, ["with", "wd"])?.output()?;
+  make_test_command(&test_bed, ["without", "pp"])?.output()?;
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Wendy Darling <wendy.darling@example.com>
+  Co-authored-by: Captain Hook <hook@jolly-roger.sea>
+  # END GIT-SQUAD
+  ");
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["active"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - wd (Wendy Darling <wendy.darling@example.com>)
+  - unknown (Captain Hook <hook@jolly-roger.sea>)
+  Run `git squad adopt` to add unknown co-authors as buddies.
+
+  ----- stderr -----
+  ");
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["alone"])?, @r#"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Removed all buddies from the current session
+  Kept unknown co-author 'Captain Hook <hook@jolly-roger.sea>', remove it with `git squad without "hook@jolly-roger.sea"`
+
+  ----- stderr -----
+  "#);
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Captain Hook <hook@jolly-roger.sea>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_unknown_active_formats() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_unknown()?;
+
+  let mut cmd = make_test_command(&test_bed, ["--format", "json", "active"])?;
+  assert_cmd_snapshot!(cmd, @r#"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  [
+    {
+      "alias": "pp",
+      "name": "Peter Pan",
+      "email": "peter.pan@example.com",
+      "active": true,
+      "known": true
+    },
+    {
+      "alias": null,
+      "name": "Captain Hook",
+      "email": "hook@jolly-roger.sea",
+      "active": true,
+      "known": false
+    }
+  ]
+
+  ----- stderr -----
+  "#);
+
+  let mut cmd = make_test_command(&test_bed, ["--format", "tsv", "active"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  alias	name	email	active	known
+  pp	Peter Pan	peter.pan@example.com	true	true
+  	Captain Hook	hook@jolly-roger.sea	true	false
+
+  ----- stderr -----
+  ");
+
+  let mut cmd = make_test_command(&test_bed, ["--porcelain", "active"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  A pp peter.pan@example.com Peter Pan
+  U - hook@jolly-roger.sea Captain Hook
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_unknown_without() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_unknown()?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["without", "HOOK@jolly-roger.sea"])?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Removed unknown co-author 'HOOK@jolly-roger.sea' from the current session
+
+  ----- stderr -----
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_unknown_adopt() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_unknown()?;
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["adopt", "hook@jolly-roger.sea", "--alias", "hook"],
+  )?;
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Adopted 'Captain Hook <hook@jolly-roger.sea>' as buddy 'hook'
+
+  ----- stderr -----
+  ");
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["active"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Active buddies in the current session:
+  - pp (Peter Pan <peter.pan@example.com>)
+  - hook (Captain Hook <hook@jolly-roger.sea>)
+
+  ----- stderr -----
+  ");
+
+  let buddies_file = read_buddies_file(&test_bed)?;
+  assert_snapshot!(buddies_file, @r#"
+  [[buddies]]
+  alias = "pp"
+  name = "Peter Pan"
+  email = "peter.pan@example.com"
+
+  [[buddies]]
+  alias = "wd"
+  name = "Wendy Darling"
+  email = "wendy.darling@example.com"
+
+  [[buddies]]
+  alias = "hook"
+  name = "Captain Hook"
+  email = "hook@jolly-roger.sea"
+  "#);
+
+  assert_cmd_snapshot!(make_test_command(&test_bed, ["adopt"])?, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  No unknown co-authors in the current session.
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
@@ -0,0 +1,154 @@
+use std::fs;
+
+use common::{
+  TestBed, commit, create_test_buddies, make_test_command, setup_git_repo,
+  with_sha_filters,
+};
+use insta_cmd::assert_cmd_snapshot;
+
+mod common;
+
+/// Sets up a repository with a base commit followed by a good and a bad one.
+fn setup_commits() -> Result<TestBed, Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  commit(&test_bed, "Base")?;
+  commit(
+    &test_bed,
+    "Fix the clock\n\n\
+     Co-authored-by: Peter Pan <peter.pan@example.com>",
+  )?;
+  commit(
+    &test_bed,
+    "Sail away\n\n\
+     Co-authored-by: Wendy <wendy@old-mail.example.org>",
+  )?;
+
+  Ok(test_bed)
+}
+
+#[test]
+fn test_verify() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_commits()?;
+
+  let mut cmd = make_test_command(&test_bed, ["verify", "HEAD~2..HEAD"])?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    ok     [SHORT_SHA] Fix the clock
+    FAILED [SHORT_SHA] Sail away
+      - Unknown co-author 'wendy@old-mail.example.org'
+    Verified 2 commit(s) in HEAD~2..HEAD, 1 failed.
+
+    ----- stderr -----
+    Error: Found problems in 1 of 2 commit(s)
+    ");
+  });
+
+  let mut cmd = make_test_command(&test_bed, ["verify", "HEAD~2..HEAD~1"])?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ok     [SHORT_SHA] Fix the clock
+    Verified 1 commit(s) in HEAD~2..HEAD~1, 0 failed.
+
+    ----- stderr -----
+    ");
+  });
+
+  Ok(())
+}
+
+#[test]
+fn test_verify_reports() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_commits()?;
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["verify", "HEAD~2..HEAD", "--report", "json"],
+  )?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    [
+      {
+        "sha": "[SHA]",
+        "subject": "Fix the clock",
+        "problems": []
+      },
+      {
+        "sha": "[SHA]",
+        "subject": "Sail away",
+        "problems": [
+          "Unknown co-author 'wendy@old-mail.example.org'"
+        ]
+      }
+    ]
+
+    ----- stderr -----
+    Error: Found problems in 1 of 2 commit(s)
+    "#);
+  });
+
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["verify", "HEAD~2..HEAD", "--report", "junit"],
+  )?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+    <?xml version="1.0" encoding="UTF-8"?>
+    <testsuite name="git-squad verify HEAD~2..HEAD" tests="2" failures="1">
+      <testcase classname="co-authors" name="[SHA] Fix the clock"/>
+      <testcase classname="co-authors" name="[SHA] Sail away">
+        <failure message="1 problem(s)">Unknown co-author &apos;wendy@old-mail.example.org&apos;</failure>
+      </testcase>
+    </testsuite>
+
+    ----- stderr -----
+    Error: Found problems in 1 of 2 commit(s)
+    "#);
+  });
+
+  Ok(())
+}
+
+#[test]
+fn test_verify_mailmap() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_commits()?;
+
+  fs::write(
+    test_bed.path().join(".mailmap"),
+    "Wendy Darling <wendy.darling@example.com> <wendy@old-mail.example.org>\n",
+  )?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["verify", "HEAD~1..HEAD", "--mailmap"])?;
+
+  with_sha_filters(|| {
+    assert_cmd_snapshot!(cmd, @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    ok     [SHORT_SHA] Sail away
+    Verified 1 commit(s) in HEAD~1..HEAD, 0 failed.
+
+    ----- stderr -----
+    ");
+  });
+
+  Ok(())
+}
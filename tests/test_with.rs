@@ -1,5 +1,8 @@
+use std::process::Command;
+
 use common::{
-  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
+  create_buddy, create_test_buddies, make_test_command, read_commit_template,
+  setup_git_repo, spawn_test_command,
 };
 use insta::assert_snapshot;
 use insta_cmd::assert_cmd_snapshot;
@@ -73,3 +76,235 @@ fn test_with_already_active() -> Result<(), Box<dyn std::error::Error>> {
 
   Ok(())
 }
+
+#[test]
+fn test_with_fuzzy() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "ppp", "wen"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Added buddy 'pp' to the current session
+  Added buddy 'wd' to the current session
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_suggestions() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "dw"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+
+  ----- stderr -----
+  Buddy with alias 'dw' does not exist
+  Did you mean:
+    wd (Wendy Darling <wendy.darling@example.com>)
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_ambiguous() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  create_buddy(&test_bed, "jd", "John Darling", "john.darling@example.com")?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "darl"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+
+  ----- stderr -----
+  Several buddies match 'darl': wd, jd
+  ");
+
+  let mut proc = spawn_test_command(&test_bed, ["with", "darl"])?;
+  proc.exp_string("Several buddies match 'darl', choose one")?;
+  proc.send("\x1b[B")?;
+  proc.send_line("")?;
+  proc.exp_string("Added buddy 'jd' to the current session")?;
+  proc.exp_eof()?;
+
+  Ok(())
+}
+
+#[test]
+fn test_with_strict() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["with", "--strict", "pp", "hook"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 3
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy with alias 'hook' does not exist
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"Test commit template");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_strict_fuzzy() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "--strict", "ppp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 3
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy with alias 'ppp' does not exist
+  Did you mean:
+    pp (Peter Pan <peter.pan@example.com>)
+  ");
+
+  // Exact emails are accepted
+  let mut cmd = make_test_command(
+    &test_bed,
+    ["with", "--strict", "wendy.darling@example.com"],
+  )?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Added buddy 'wd' to the current session
+
+  ----- stderr -----
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_strict_already_active() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  Command::new("git")
+    .args(["config", "squad.strict", "true"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "wd", "pp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 5
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy 'pp' is already active
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_no_template() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  Command::new("git")
+    .args(["config", "--unset", "commit.template"])
+    .current_dir(test_bed.path())
+    .output()?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "pp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 7
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: No template file set. Configure one using `git config commit.template /path/to/template/file`
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_self() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  create_buddy(&test_bed, "me", "Test User", "test@example.com")?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "me", "pp"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+  Added buddy 'pp' to the current session
+
+  ----- stderr -----
+  Warning: Skipping buddy 'me', that's you. You can't be your own co-author
+  ");
+
+  Ok(())
+}
+
+#[test]
+fn test_with_self_mailmap() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+  create_buddy(&test_bed, "me", "Test User", "test.user@old.example.com")?;
+
+  std::fs::write(
+    test_bed.path().join(".mailmap"),
+    "Test User <test@example.com> <test.user@old.example.com>\n",
+  )?;
+
+  let mut cmd = make_test_command(&test_bed, ["with", "me"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: true
+  exit_code: 0
+  ----- stdout -----
+
+  ----- stderr -----
+  Warning: Skipping buddy 'me', that's you. You can't be your own co-author
+  ");
+
+  Ok(())
+}
@@ -1,5 +1,5 @@
 use common::{
-  create_test_buddies, make_test_command, read_commit_template, setup_git_repo
+  create_test_buddies, make_test_command, read_commit_template, setup_git_repo,
 };
 use insta::assert_snapshot;
 use insta_cmd::assert_cmd_snapshot;
@@ -66,3 +66,34 @@ fn test_without_inactive() -> Result<(), Box<dyn std::error::Error>> {
 
   Ok(())
 }
+
+#[test]
+fn test_without_strict() -> Result<(), Box<dyn std::error::Error>> {
+  let test_bed = setup_git_repo()?;
+  create_test_buddies(&test_bed)?;
+
+  make_test_command(&test_bed, ["with", "pp"])?.output()?;
+
+  let mut cmd =
+    make_test_command(&test_bed, ["without", "--strict", "pp", "wd"])?;
+
+  assert_cmd_snapshot!(cmd, @"
+  success: false
+  exit_code: 6
+  ----- stdout -----
+
+  ----- stderr -----
+  Error: Buddy 'wd' is not active
+  ");
+
+  let commit_template = read_commit_template(&test_bed)?;
+  assert_snapshot!(commit_template, @"
+  Test commit template
+
+  # BEGIN GIT-SQUAD
+  Co-authored-by: Peter Pan <peter.pan@example.com>
+  # END GIT-SQUAD
+  ");
+
+  Ok(())
+}